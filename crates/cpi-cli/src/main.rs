@@ -0,0 +1,619 @@
+// Command-line front end to the calculator, for scripting bulk checks.
+//
+//   cpi spi --sem 3 --grades AA,AB,BB,...
+//   cpi cpi --sem 4 --prev 8.2 --spi 9
+//   cpi transcript --file grades.toml --json
+
+mod shared;
+
+use clap::{Args, Parser, Subcommand};
+use cpi_core::export::json_number;
+use cpi_core::functions::{
+    self, CalcError, CourseRef, Repeat, RepeatPolicy, SemesterResult, TranscriptEntry,
+};
+use cpi_core::grades::{self, GradeScale};
+use cpi_core::semdata::{self, SemesterId, Track};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "cpi", version, about = "SPI and CPI calculator")]
+struct Cli {
+    /// Programme id, e.g. cse
+    #[arg(short, long, global = true, default_value = "cse")]
+    programme: String,
+    /// Admission year; defaults to the newest batch with a curriculum
+    #[arg(short, long, global = true)]
+    batch: Option<u16>,
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// SPI of one semester from its grades
+    Spi(SemesterArgs),
+    /// CPI after a semester from the previous CPI and that semester's SPI or grades
+    Cpi {
+        #[command(flatten)]
+        semester: SemesterArgs,
+        /// CPI up to the previous semester; not needed for semester 1
+        #[arg(long)]
+        prev: Option<f32>,
+        /// SPI of the semester, instead of --grades
+        #[arg(long, conflicts_with = "grades", required_unless_present = "grades")]
+        spi: Option<f32>,
+        /// Track taken in an earlier semester that had several, e.g. 7=project
+        #[arg(long = "path", value_name = "SEM=TRACK", value_parser = parse_path_track)]
+        path: Vec<(u8, Track)>,
+        /// Elective taken in an earlier semester, by semester and course position, e.g. 7:3=CS441
+        #[arg(long = "path-elective", value_name = "SEM:COURSE=CODE", value_parser = parse_path_elective)]
+        path_electives: Vec<(u8, (usize, String))>,
+    },
+    /// SPI and CPI for every semester of a transcript file (TOML or JSON)
+    Transcript {
+        /// Transcript file; read as JSON if it ends in .json, TOML otherwise
+        #[arg(long)]
+        file: PathBuf,
+        /// How cleared backlogs count; overrides the file's `policy`
+        #[arg(long)]
+        policy: Option<RepeatPolicy>,
+    },
+}
+
+#[derive(Args)]
+struct SemesterArgs {
+    /// Semester number
+    #[arg(long)]
+    sem: u8,
+    /// For semesters offered in several tracks: electives, project or internship
+    #[arg(long)]
+    track: Option<Track>,
+    /// Letter grades or grade points, one per course in curriculum order
+    #[arg(long, value_delimiter = ',')]
+    grades: Vec<String>,
+    /// Elective filling a placeholder slot, by course position, e.g. 5=HS211
+    #[arg(long = "elective", value_name = "COURSE=CODE", value_parser = parse_elective)]
+    electives: Vec<(usize, String)>,
+}
+
+impl SemesterArgs {
+    fn id(&self) -> SemesterId {
+        SemesterId {
+            number: self.sem,
+            track: self.track,
+        }
+    }
+
+    fn electives(&self) -> Vec<Option<String>> {
+        slot_electives(&self.electives)
+    }
+}
+
+// Elective codes per course slot, as `resolve_semester` takes them, from
+// (course position, code) pairs
+fn slot_electives<'a>(
+    chosen: impl IntoIterator<Item = &'a (usize, String)>,
+) -> Vec<Option<String>> {
+    let mut electives = Vec::new();
+    for (position, code) in chosen {
+        if electives.len() < *position {
+            electives.resize(*position, None);
+        }
+        electives[position - 1] = Some(code.clone());
+    }
+    electives
+}
+
+fn parse_path_track(value: &str) -> Result<(u8, Track), String> {
+    let (number, track) = value.split_once('=').ok_or("expected SEM=TRACK")?;
+    Ok((
+        number
+            .parse()
+            .map_err(|_| format!("bad semester '{}'", number))?,
+        track.parse()?,
+    ))
+}
+
+fn parse_path_elective(value: &str) -> Result<(u8, (usize, String)), String> {
+    let (number, elective) = value.split_once(':').ok_or("expected SEM:COURSE=CODE")?;
+    Ok((
+        number
+            .parse()
+            .map_err(|_| format!("bad semester '{}'", number))?,
+        parse_elective(elective)?,
+    ))
+}
+
+fn parse_elective(value: &str) -> Result<(usize, String), String> {
+    let (position, code) = value.split_once('=').ok_or("expected COURSE=CODE")?;
+    match position.parse() {
+        Ok(position) if position >= 1 => Ok((position, code.to_string())),
+        _ => Err(format!("bad course position '{}'", position)),
+    }
+}
+
+// --- Transcript file ---
+// Semesters are listed in order from semester 1, like the web transcript
+// editor. Electives are given per course slot, "" keeping the placeholder;
+// repeats name the failed course by semester and code. A programme or batch
+// given in the file wins over the command-line options.
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TranscriptFile {
+    programme: Option<String>,
+    batch: Option<u16>,
+    policy: Option<RepeatPolicy>,
+    semester: Vec<TranscriptSemester>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TranscriptSemester {
+    track: Option<Track>,
+    grades: Vec<String>,
+    #[serde(default)]
+    electives: Vec<String>,
+    #[serde(default)]
+    repeat: Vec<TranscriptRepeat>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TranscriptRepeat {
+    semester: u8,
+    code: String,
+    grade: String,
+}
+
+enum CliError {
+    Calc(CalcError),
+    Input(String),
+    Output(io::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Calc(err @ CalcError::UnknownProgramme(_)) => {
+                let known: Vec<&str> = semdata::programmes()
+                    .iter()
+                    .map(|p| p.id.as_str())
+                    .collect();
+                write!(f, "{} (known: {})", err, known.join(", "))
+            }
+            CliError::Calc(err) => write!(f, "{}", err),
+            CliError::Input(msg) => f.write_str(msg),
+            CliError::Output(err) => write!(f, "cannot write output: {}", err),
+        }
+    }
+}
+
+impl CliError {
+    // The reader went away (e.g. `| head`); nothing left to say
+    fn is_broken_pipe(&self) -> bool {
+        matches!(self, CliError::Output(err) if err.kind() == io::ErrorKind::BrokenPipe)
+    }
+}
+
+impl From<CalcError> for CliError {
+    fn from(err: CalcError) -> Self {
+        CliError::Calc(err)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::Output(err)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut out = io::stdout().lock();
+    match run(&cli, &mut out).and_then(|()| out.flush().map_err(CliError::from)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.is_broken_pipe() => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: &Cli, out: &mut impl Write) -> Result<(), CliError> {
+    let scale = grades::default_scale();
+    match &cli.command {
+        Command::Spi(semester) => {
+            let batch = functions::batch_for(&cli.programme, cli.batch, semdata::current_year())?;
+            let spi = semester_spi(&cli.programme, batch, semester, scale)?;
+            if cli.json {
+                writeln!(
+                    out,
+                    "{}",
+                    json!({ "semester": semester.sem, "track": semester.track, "spi": json_number(spi) })
+                )?;
+            } else {
+                writeln!(out, "Semester {}: SPI {:.3}", semester.id(), spi)?;
+            }
+        }
+        Command::Cpi {
+            semester,
+            prev,
+            spi,
+            path,
+            path_electives,
+        } => {
+            let batch = functions::batch_for(&cli.programme, cli.batch, semdata::current_year())?;
+            let spi = match spi {
+                Some(spi) => *spi,
+                None => semester_spi(&cli.programme, batch, semester, scale)?,
+            };
+            // Semester 1 has no earlier CPI; its CPI is its SPI
+            let prev = match prev {
+                Some(prev) => *prev,
+                None if semester.sem <= 1 => 0.0,
+                None => {
+                    return Err(CliError::Input(format!(
+                        "--prev is needed for semester {}",
+                        semester.sem
+                    )))
+                }
+            };
+            let tracks: BTreeMap<u8, Track> = path.iter().copied().collect();
+            let path: Vec<SemesterId> = (1..semester.sem)
+                .map(|number| SemesterId {
+                    number,
+                    track: tracks.get(&number).copied(),
+                })
+                .collect();
+            let path_electives: Vec<Vec<Option<String>>> = (1..semester.sem)
+                .map(|number| {
+                    slot_electives(
+                        path_electives
+                            .iter()
+                            .filter(|(n, _)| *n == number)
+                            .map(|(_, elective)| elective),
+                    )
+                })
+                .collect();
+            let cpi = functions::calculate_cpi_option3(
+                &cli.programme,
+                batch,
+                semester.id(),
+                &semester.electives(),
+                &path,
+                &path_electives,
+                prev,
+                spi,
+            )?;
+            if cli.json {
+                writeln!(
+                    out,
+                    "{}",
+                    json!({ "semester": semester.sem, "track": semester.track, "spi": json_number(spi), "cpi": json_number(cpi) })
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "Semester {}: SPI {:.3}, CPI {:.3}",
+                    semester.id(),
+                    spi,
+                    cpi
+                )?;
+            }
+        }
+        Command::Transcript { file, policy } => {
+            let transcript = read_transcript(file)?;
+            let programme = transcript
+                .programme
+                .clone()
+                .unwrap_or_else(|| cli.programme.clone());
+            let batch = functions::batch_for(
+                &programme,
+                transcript.batch.or(cli.batch),
+                semdata::current_year(),
+            )?;
+            let policy = policy.or(transcript.policy).unwrap_or_default();
+            let entries = transcript_entries(&programme, batch, &transcript)?;
+            let results =
+                functions::calculate_transcript(&programme, batch, &entries, scale, policy)?;
+            if cli.json {
+                let semesters: Vec<serde_json::Value> = results.iter().map(result_json).collect();
+                writeln!(
+                    out,
+                    "{}",
+                    serde_json::to_string_pretty(&semesters).unwrap_or_default()
+                )?;
+            } else {
+                print_transcript(out, &results)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn semester_spi(
+    programme: &str,
+    batch: u16,
+    semester: &SemesterArgs,
+    scale: &GradeScale,
+) -> Result<f32, CliError> {
+    Ok(shared::mixed_grades_spi(
+        programme,
+        batch,
+        semester.id(),
+        &semester.electives(),
+        &semester.grades,
+        scale,
+    )?)
+}
+
+fn read_transcript(path: &Path) -> Result<TranscriptFile, CliError> {
+    let src = std::fs::read_to_string(path)
+        .map_err(|e| CliError::Input(format!("cannot read {}: {}", path.display(), e)))?;
+    let parsed = if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    {
+        serde_json::from_str(&src).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(&src).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|e| CliError::Input(format!("cannot parse {}: {}", path.display(), e)))
+}
+
+fn transcript_entries(
+    programme: &str,
+    batch: u16,
+    transcript: &TranscriptFile,
+) -> Result<Vec<TranscriptEntry>, CliError> {
+    let curriculum =
+        semdata::get_curriculum(programme, batch).ok_or(CalcError::UnknownCurriculum {
+            programme: programme.to_string(),
+            batch,
+        })?;
+    let mut entries: Vec<TranscriptEntry> = Vec::with_capacity(transcript.semester.len());
+    for (i, row) in transcript.semester.iter().enumerate() {
+        let id = SemesterId {
+            number: i as u8 + 1,
+            track: row.track,
+        };
+        let repeats = row
+            .repeat
+            .iter()
+            .map(|repeat| {
+                // Failed courses are found by code among those actually taken
+                let taken = entries
+                    .get((repeat.semester as usize).wrapping_sub(1))
+                    .ok_or_else(|| {
+                        CliError::Input(format!(
+                            "semester {}: repeat of semester {}, which is not earlier",
+                            id, repeat.semester
+                        ))
+                    })?;
+                let course =
+                    functions::resolve_semester(curriculum, taken.semester, &taken.electives)?
+                        .course_code
+                        .iter()
+                        .position(|code| code.eq_ignore_ascii_case(&repeat.code))
+                        .ok_or_else(|| {
+                            CliError::Input(format!(
+                                "semester {} has no course {}",
+                                taken.semester, repeat.code
+                            ))
+                        })?;
+                Ok(Repeat {
+                    course: CourseRef {
+                        semester: repeat.semester,
+                        course,
+                    },
+                    grade: repeat.grade.clone(),
+                })
+            })
+            .collect::<Result<_, CliError>>()?;
+        entries.push(TranscriptEntry {
+            semester: id,
+            grades: row.grades.clone(),
+            electives: row
+                .electives
+                .iter()
+                .map(|code| Some(code.clone()).filter(|c| !c.trim().is_empty()))
+                .collect(),
+            repeats,
+        });
+    }
+    Ok(entries)
+}
+
+fn print_transcript(out: &mut impl Write, results: &[SemesterResult]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<18} {:>8} {:>7} {:>11} {:>7}  Backlogs",
+        "Semester", "Credits", "SPI", "Cumulative", "CPI"
+    )?;
+    for result in results {
+        let backlogs: Vec<&str> = result.backlogs.iter().map(|b| b.code.as_str()).collect();
+        writeln!(
+            out,
+            "{:<18} {:>8} {:>7.3} {:>11} {:>7.3}  {}",
+            result.semester.to_string(),
+            result.credits,
+            result.spi,
+            result.cumulative_credits,
+            result.cpi,
+            if backlogs.is_empty() {
+                "-".to_string()
+            } else {
+                backlogs.join(", ")
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn result_json(result: &SemesterResult) -> serde_json::Value {
+    json!({
+        "semester": result.semester.number,
+        "track": result.semester.track,
+        "credits": json_number(result.credits),
+        "grade_points": json_number(result.grade_points),
+        "spi": json_number(result.spi),
+        "cumulative_credits": json_number(result.cumulative_credits),
+        "cpi": json_number(result.cpi),
+        "courses": result.courses.iter().map(|c| json!({
+            "code": c.code,
+            "name": c.name,
+            "credit": json_number(c.credit),
+            "grade": c.grade,
+            "repeat": c.repeat,
+        })).collect::<Vec<_>>(),
+        "backlogs": result.backlogs.iter().map(|b| json!({
+            "semester": b.course.semester,
+            "code": b.code,
+            "name": b.name,
+            "credit": json_number(b.credit),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A stdout whose reader has gone away
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn run_args(args: &[&str]) -> Result<String, CliError> {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let mut out = Vec::new();
+        run(&cli, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn path_options_are_parsed() {
+        assert_eq!(parse_path_track("7=project"), Ok((7, Track::Project)));
+        assert!(parse_path_track("7").is_err());
+        assert!(parse_path_track("seven=project").is_err());
+        assert!(parse_path_track("7=sabbatical").is_err());
+
+        assert_eq!(
+            parse_path_elective("7:3=CS441"),
+            Ok((7, (3, "CS441".to_string())))
+        );
+        assert!(parse_path_elective("7=CS441").is_err());
+        assert!(parse_path_elective("7:0=CS441").is_err());
+        assert_eq!(
+            slot_electives(&[(3, "CS441".to_string())]),
+            [None, None, Some("CS441".to_string())]
+        );
+    }
+
+    #[test]
+    fn cpi_follows_the_path_given_on_the_command_line() {
+        let out = run_args(&[
+            "cpi",
+            "--json",
+            "cpi",
+            "--sem",
+            "8",
+            "--track",
+            "project",
+            "--prev",
+            "8",
+            "--spi",
+            "9",
+            "--path",
+            "7=project",
+            "--path-elective",
+            "7:3=CS441",
+        ])
+        .unwrap_or_else(|err| panic!("{}", err));
+        let found: serde_json::Value = serde_json::from_str(&out).unwrap();
+        // (8 * 294 + 9 * 30) / 324
+        assert!((found["cpi"].as_f64().unwrap() - 2622.0 / 324.0).abs() < 1e-5);
+
+        let err = run_args(&["cpi", "cpi", "--sem", "2", "--spi", "9"]).err();
+        assert_eq!(
+            err.map(|e| e.to_string()).as_deref(),
+            Some("--prev is needed for semester 2")
+        );
+    }
+
+    #[test]
+    fn repeats_are_found_by_code_in_earlier_semesters() {
+        let transcript: TranscriptFile = toml::from_str(
+            r#"
+            [[semester]]
+            grades = ["AA", "AB", "BB", "FF", "AA", "AB", "AA", "BB"]
+
+            [[semester]]
+            grades = ["AA"]
+            repeat = [{ semester = 1, code = "ec101", grade = "BC" }]
+            "#,
+        )
+        .unwrap();
+        let entries =
+            transcript_entries("cse", 2021, &transcript).unwrap_or_else(|err| panic!("{}", err));
+        assert_eq!(
+            entries[1].repeats,
+            [Repeat {
+                course: CourseRef {
+                    semester: 1,
+                    course: 3,
+                },
+                grade: "BC".to_string(),
+            }]
+        );
+
+        let transcript: TranscriptFile = toml::from_str(
+            r#"
+            [[semester]]
+            grades = ["AA"]
+            repeat = [{ semester = 1, code = "EC101", grade = "BC" }]
+            "#,
+        )
+        .unwrap();
+        let err = transcript_entries("cse", 2021, &transcript).err();
+        assert_eq!(
+            err.map(|e| e.to_string()).as_deref(),
+            Some("semester 1: repeat of semester 1, which is not earlier")
+        );
+    }
+
+    #[test]
+    fn a_closed_pipe_is_not_an_error() {
+        let cli = Cli::try_parse_from([
+            "cpi",
+            "spi",
+            "--sem",
+            "1",
+            "--grades",
+            "AA,AA,AA,AA,AA,AA,AA,AA",
+        ])
+        .unwrap();
+        let err = run(&cli, &mut ClosedPipe).err().unwrap();
+        assert!(err.is_broken_pipe());
+        assert!(!CliError::Input("bad".to_string()).is_broken_pipe());
+    }
+}
@@ -0,0 +1,460 @@
+// HTTP JSON API over the calculator, for other pages and test suites to call.
+// Built only with the `server` feature:
+//
+//   cargo run -p cpi-cli --features server --bin cpi-server -- --addr 127.0.0.1:8080
+//
+//   GET  /curricula                         programmes and their batches
+//   GET  /curricula/{programme}/{semester}  courses, ?batch=2021&track=project
+//   POST /spi                               {"programme", "semester", "grades", ...}
+//   POST /cpi                               the same plus "prev_cpi" (not for semester 1), and "spi" instead of grades
+//
+// Failures answer with a 4xx status and {"error": {"kind", "message"}}, plus
+// "course" (the course index, from 0) when one course is at fault.
+
+mod shared;
+
+use clap::Parser;
+use cpi_core::export::json_number;
+use cpi_core::functions::{self, CalcError};
+use cpi_core::grades;
+use cpi_core::semdata::{self, SemesterId, Track};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::process::ExitCode;
+use tiny_http::{Header, Method, Response, Server};
+
+// Largest request body read; grade lists are far smaller.
+const MAX_BODY: u64 = 64 * 1024;
+
+#[derive(Parser)]
+#[command(
+    name = "cpi-server",
+    version,
+    about = "HTTP JSON API for the SPI and CPI calculator"
+)]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    addr: String,
+    /// Access-Control-Allow-Origin sent with every response, so pages on
+    /// other origins (e.g. a department portal) can call the API
+    #[arg(long, default_value = "*")]
+    allow_origin: String,
+}
+
+// Body of POST /spi and POST /cpi. The batch defaults to the newest one with a
+// curriculum; grades may mix letter grades and grade points.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CalcRequest {
+    programme: String,
+    batch: Option<u16>,
+    semester: u8,
+    track: Option<Track>,
+    #[serde(default)]
+    grades: Vec<String>,
+    #[serde(default)]
+    electives: Vec<Option<String>>, // Elective code per course slot, null keeping the placeholder
+    // CPI only
+    prev_cpi: Option<f32>,
+    spi: Option<f32>, // Instead of grades
+    #[serde(default)]
+    path: BTreeMap<u8, Track>, // Tracks taken in earlier semesters that had several, e.g. {"7": "project"}
+    #[serde(default)]
+    path_electives: BTreeMap<u8, Vec<Option<String>>>, // Electives of earlier semesters, like `electives`
+}
+
+impl CalcRequest {
+    fn id(&self) -> SemesterId {
+        SemesterId {
+            number: self.semester,
+            track: self.track,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    kind: &'static str, // Stable snake_case name for clients to match on, as `CalcError::kind`
+    message: String,
+    course: Option<usize>,
+}
+
+impl ApiError {
+    fn new(status: u16, kind: &'static str, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            kind,
+            message: message.into(),
+            course: None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut error = json!({ "kind": self.kind, "message": self.message });
+        if let Some(course) = self.course {
+            error["course"] = json!(course);
+        }
+        json!({ "error": error })
+    }
+}
+
+impl From<CalcError> for ApiError {
+    fn from(err: CalcError) -> Self {
+        let status = match err {
+            CalcError::UnknownProgramme(_)
+            | CalcError::NoCurricula(_)
+            | CalcError::UnknownCurriculum { .. }
+            | CalcError::UnknownSemester(_) => 404,
+            _ => 400,
+        };
+        ApiError {
+            status,
+            kind: err.kind(),
+            message: err.to_string(),
+            course: err.course(),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let headers = match response_headers(&cli.allow_origin) {
+        Some(headers) => headers,
+        None => {
+            eprintln!("error: '{}' is not a valid header value", cli.allow_origin);
+            return ExitCode::FAILURE;
+        }
+    };
+    let server = match Server::http(&cli.addr) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("error: cannot listen on {}: {}", cli.addr, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("listening on http://{}", cli.addr);
+
+    for mut request in server.incoming_requests() {
+        let result = if *request.method() == Method::Options {
+            // CORS preflight; the headers below are the whole answer
+            Ok(Value::Null)
+        } else {
+            let mut body = String::new();
+            match request
+                .as_reader()
+                .take(MAX_BODY + 1)
+                .read_to_string(&mut body)
+            {
+                Err(_) => Err(ApiError::new(
+                    400,
+                    "invalid_body",
+                    "request body is not UTF-8 text",
+                )),
+                Ok(read) if read as u64 > MAX_BODY => Err(ApiError::new(
+                    413,
+                    "body_too_large",
+                    format!("request body is over {} bytes", MAX_BODY),
+                )),
+                Ok(_) => route(request.method(), request.url(), &body),
+            }
+        };
+        let (status, body) = match result {
+            Ok(Value::Null) => (204, String::new()),
+            Ok(value) => (200, value.to_string()),
+            Err(err) => (err.status, err.to_json().to_string()),
+        };
+        eprintln!("{} {} {}", request.method(), request.url(), status);
+        let mut response = Response::from_string(body).with_status_code(status);
+        for header in &headers {
+            response.add_header(header.clone());
+        }
+        // Nothing to do if the client has gone away
+        let _ = request.respond(response);
+    }
+    ExitCode::SUCCESS
+}
+
+fn response_headers(allow_origin: &str) -> Option<Vec<Header>> {
+    [
+        ("Content-Type", "application/json"),
+        ("Access-Control-Allow-Origin", allow_origin),
+        ("Access-Control-Allow-Methods", "GET, POST, OPTIONS"),
+        ("Access-Control-Allow-Headers", "Content-Type"),
+    ]
+    .into_iter()
+    .map(|(name, value)| Header::from_bytes(name, value).ok())
+    .collect()
+}
+
+fn route(method: &Method, url: &str, body: &str) -> Result<Value, ApiError> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        (Method::Get, ["curricula"]) => Ok(list_programmes()),
+        (Method::Get, ["curricula", programme, semester]) => {
+            semester_courses(programme, semester, query)
+        }
+        (Method::Post, ["spi"]) => spi(parse_body(body)?),
+        (Method::Post, ["cpi"]) => cpi(parse_body(body)?),
+        (_, ["curricula"]) | (_, ["curricula", _, _]) | (_, ["spi"]) | (_, ["cpi"]) => {
+            Err(ApiError::new(
+                405,
+                "method_not_allowed",
+                format!("{} is not supported on {}", method, path),
+            ))
+        }
+        _ => Err(ApiError::new(
+            404,
+            "not_found",
+            format!("no endpoint at {}", path),
+        )),
+    }
+}
+
+fn parse_body(body: &str) -> Result<CalcRequest, ApiError> {
+    serde_json::from_str(body)
+        .map_err(|e| ApiError::new(400, "invalid_body", format!("invalid request body: {}", e)))
+}
+
+fn list_programmes() -> Value {
+    let latest = semdata::current_year();
+    let programmes: Vec<Value> = semdata::programmes()
+        .iter()
+        .map(|p| json!({ "id": p.id, "name": p.name, "batches": p.batches(latest) }))
+        .collect();
+    json!({ "programmes": programmes })
+}
+
+// Query parameters, percent-decoded, with `+` standing for a space as in forms
+fn query_params(query: &str) -> Result<BTreeMap<String, String>, ApiError> {
+    let decode = |raw: &str| {
+        urlencoding::decode(&raw.replace('+', " "))
+            .map(|v| v.into_owned())
+            .map_err(|_| ApiError::new(400, "invalid_query", format!("bad escape in '{}'", raw)))
+    };
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| Ok((decode(key)?, decode(value)?)))
+        .collect()
+}
+
+fn semester_courses(programme: &str, semester: &str, query: &str) -> Result<Value, ApiError> {
+    let params = query_params(query)?;
+    let batch =
+        match params.get("batch") {
+            Some(batch) => Some(batch.parse::<u16>().map_err(|_| {
+                ApiError::new(400, "invalid_query", format!("bad batch '{}'", batch))
+            })?),
+            None => None,
+        };
+    let batch = functions::batch_for(programme, batch, semdata::current_year())?;
+    let sem_no = semester
+        .parse::<u8>()
+        .map_err(|_| ApiError::new(400, "invalid_query", format!("bad semester '{}'", semester)))?;
+    let track = match params.get("track") {
+        Some(track) => Some(
+            track
+                .parse::<Track>()
+                .map_err(|e| ApiError::new(400, "invalid_query", e))?,
+        ),
+        None => None,
+    };
+    let curriculum =
+        semdata::get_curriculum(programme, batch).ok_or(CalcError::UnknownCurriculum {
+            programme: programme.to_string(),
+            batch,
+        })?;
+    if track.is_none() && !curriculum.tracks(sem_no).is_empty() {
+        return Err(CalcError::TrackNotChosen(sem_no).into());
+    }
+    let id = SemesterId {
+        number: sem_no,
+        track,
+    };
+    let sem = curriculum
+        .semester(id)
+        .ok_or(CalcError::UnknownSemester(id))?;
+    let courses: Vec<Value> = (0..sem.course_code.len())
+        .map(|index| {
+            let electives: Vec<Value> = curriculum
+                .electives_for(id, index)
+                .into_iter()
+                .map(|e| json!({ "code": e.code, "name": e.name, "credit": json_number(e.credit) }))
+                .collect();
+            json!({
+                "code": sem.course_code[index],
+                "name": sem.course_name[index],
+                "credit": json_number(sem.course_credit[index]),
+                "electives": electives,
+            })
+        })
+        .collect();
+    Ok(json!({
+        "programme": programme,
+        "batch": batch,
+        "semester": sem_no,
+        "track": track,
+        "total_credit": json_number(sem.total_credit),
+        "courses": courses,
+    }))
+}
+
+fn spi(req: CalcRequest) -> Result<Value, ApiError> {
+    let batch = functions::batch_for(&req.programme, req.batch, semdata::current_year())?;
+    let spi = shared::mixed_grades_spi(
+        &req.programme,
+        batch,
+        req.id(),
+        &req.electives,
+        &req.grades,
+        grades::default_scale(),
+    )?;
+    Ok(
+        json!({ "programme": req.programme, "batch": batch, "semester": req.semester, "track": req.track, "spi": json_number(spi) }),
+    )
+}
+
+fn cpi(req: CalcRequest) -> Result<Value, ApiError> {
+    let batch = functions::batch_for(&req.programme, req.batch, semdata::current_year())?;
+    // Semester 1 has no earlier CPI; its CPI is its SPI
+    let prev_cpi = match req.prev_cpi {
+        Some(prev_cpi) => prev_cpi,
+        None if req.semester <= 1 => 0.0,
+        None => {
+            return Err(ApiError::new(
+                400,
+                "invalid_body",
+                "missing field `prev_cpi`",
+            ))
+        }
+    };
+    let spi = match (req.spi, req.grades.is_empty()) {
+        (Some(spi), true) => spi,
+        (None, false) => shared::mixed_grades_spi(
+            &req.programme,
+            batch,
+            req.id(),
+            &req.electives,
+            &req.grades,
+            grades::default_scale(),
+        )?,
+        _ => {
+            return Err(ApiError::new(
+                400,
+                "invalid_body",
+                "give either `spi` or `grades`",
+            ))
+        }
+    };
+    let path: Vec<SemesterId> = (1..req.semester)
+        .map(|number| SemesterId {
+            number,
+            track: req.path.get(&number).copied(),
+        })
+        .collect();
+    let path_electives: Vec<Vec<Option<String>>> = (1..req.semester)
+        .map(|number| req.path_electives.get(&number).cloned().unwrap_or_default())
+        .collect();
+    let cpi = functions::calculate_cpi_option3(
+        &req.programme,
+        batch,
+        req.id(),
+        &req.electives,
+        &path,
+        &path_electives,
+        prev_cpi,
+        spi,
+    )?;
+    Ok(json!({
+        "programme": req.programme,
+        "batch": batch,
+        "semester": req.semester,
+        "track": req.track,
+        "spi": json_number(spi),
+        "cpi": json_number(cpi),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(url: &str) -> Result<Value, ApiError> {
+        route(&Method::Get, url, "")
+    }
+
+    #[test]
+    fn lists_programmes() {
+        let found = get("/curricula").unwrap();
+        let cse = found["programmes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["id"] == "cse")
+            .unwrap();
+        assert!(!cse["batches"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn lists_the_courses_of_a_track() {
+        let found = get("/curricula/cse/8?track=project").unwrap();
+        assert_eq!(found["track"], "project");
+        assert_eq!(found["total_credit"], 30.0);
+        assert_eq!(found["courses"][2]["code"], "CS410");
+
+        // Query values are percent-decoded
+        assert_eq!(get("/curricula/cse/8?track=%70roject").unwrap(), found);
+        let err = get("/curricula/cse/8?track=project%20x").unwrap_err();
+        assert_eq!((err.status, err.kind), (400, "invalid_query"));
+        assert_eq!(err.message, "unknown track 'project x'");
+
+        let err = get("/curricula/cse/8").unwrap_err();
+        assert_eq!((err.status, err.kind), (400, "track_not_chosen"));
+    }
+
+    #[test]
+    fn computes_cpi() {
+        let body = r#"{"programme": "cse", "semester": 2, "prev_cpi": 8.0, "spi": 9.0}"#;
+        let found = route(&Method::Post, "/cpi", body).unwrap();
+        assert_eq!(found["spi"], 9.0);
+        // (8 * 46 + 9 * 44) / 90
+        assert!((found["cpi"].as_f64().unwrap() - 764.0 / 90.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn first_semester_cpi_needs_no_previous_cpi() {
+        let body = r#"{"programme": "cse", "semester": 1, "spi": 8.5}"#;
+        let found = route(&Method::Post, "/cpi", body).unwrap();
+        assert_eq!(
+            (found["spi"].as_f64(), found["cpi"].as_f64()),
+            (Some(8.5), Some(8.5))
+        );
+
+        let body = r#"{"programme": "cse", "semester": 2, "spi": 8.5}"#;
+        let err = route(&Method::Post, "/cpi", body).unwrap_err();
+        assert_eq!((err.status, err.kind), (400, "invalid_body"));
+    }
+
+    #[test]
+    fn reports_calculation_errors() {
+        let body = r#"{"programme": "cse", "semester": 1, "grades": ["AA", "AB"]}"#;
+        let err = route(&Method::Post, "/spi", body).unwrap_err();
+        assert_eq!((err.status, err.kind), (400, "grade_count_mismatch"));
+        assert_eq!(
+            err.to_json()["error"]["message"],
+            "expected 8 grades, got 2"
+        );
+    }
+
+    #[test]
+    fn rejects_other_methods() {
+        let err = route(&Method::Delete, "/spi", "").unwrap_err();
+        assert_eq!((err.status, err.kind), (405, "method_not_allowed"));
+        assert_eq!(get("/nowhere").unwrap_err().status, 404);
+    }
+}
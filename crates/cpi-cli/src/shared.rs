@@ -0,0 +1,25 @@
+// Helpers shared by the `cpi` and `cpi-server` binaries.
+
+use cpi_core::functions::{self, CalcError};
+use cpi_core::grades::GradeScale;
+use cpi_core::semdata::SemesterId;
+
+// SPI from grades that may mix letter grades and grade points, e.g. "AB" and "9"
+pub fn mixed_grades_spi(
+    programme: &str,
+    batch: u16,
+    sem_id: SemesterId,
+    electives: &[Option<String>],
+    grades: &[String],
+    scale: &GradeScale,
+) -> Result<f32, CalcError> {
+    let points: Vec<f32> = grades
+        .iter()
+        .enumerate()
+        .map(|(i, grade)| match grade.trim().parse::<f32>() {
+            Ok(points) => Ok(points),
+            Err(_) => functions::grade_points(i, grade.trim(), scale),
+        })
+        .collect::<Result<_, _>>()?;
+    functions::calculate_spi_n(programme, batch, sem_id, electives, points)
+}
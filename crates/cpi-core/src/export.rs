@@ -0,0 +1,293 @@
+use crate::functions::{CalcError, SemesterResult};
+use crate::grades::GradeScale;
+use crate::semdata::{Semester, Track};
+use serde::Serialize;
+use std::fmt::Write;
+
+// Calculated results in a form advisors can open: CSV for spreadsheets, JSON
+// for scripts. Both list each course with its grade, credits and grade points
+// next to its semester's SPI and CPI.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Export {
+    pub programme: String,
+    pub batch: u16,
+    pub semesters: Vec<ExportSemester>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportSemester {
+    pub semester: u8,
+    pub track: Option<Track>,
+    pub credits: f32,
+    pub spi: f32,
+    pub cpi: Option<f32>,           // None for an SPI-only calculation
+    pub courses: Vec<ExportCourse>, // Empty when only the SPI was entered
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportCourse {
+    pub code: String,
+    pub name: String,
+    pub credit: f32,
+    pub grade: String,
+    pub points: f32,       // Points of the grade on the scale, e.g. 9 for AB
+    pub grade_points: f32, // credit * points
+    pub repeat: bool,      // A backlog retaken this semester
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    // MIME type for downloads
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Json => "application/json",
+        }
+    }
+
+    pub fn render(&self, export: &Export) -> String {
+        match self {
+            ExportFormat::Csv => to_csv(export),
+            ExportFormat::Json => to_json(export),
+        }
+    }
+}
+
+impl Export {
+    // e.g. cse-2021-semester-3.csv, or cse-2021-transcript.csv for several semesters
+    pub fn file_name(&self, format: ExportFormat) -> String {
+        match self.semesters.as_slice() {
+            [sem] => format!(
+                "{}-{}-semester-{}.{}",
+                self.programme,
+                self.batch,
+                sem.semester,
+                format.extension()
+            ),
+            _ => format!(
+                "{}-{}-transcript.{}",
+                self.programme,
+                self.batch,
+                format.extension()
+            ),
+        }
+    }
+}
+
+impl ExportSemester {
+    // One semester from its (elective-resolved) course list and the grades
+    // entered for it, in course order. `grades` may be empty when only the SPI
+    // is known; the export then has no course rows.
+    pub fn from_grades(
+        semester: &Semester,
+        grades: &[String],
+        scale: &GradeScale,
+        spi: f32,
+        cpi: Option<f32>,
+    ) -> Result<Self, CalcError> {
+        if !grades.is_empty() && grades.len() != semester.course_code.len() {
+            return Err(CalcError::GradeCountMismatch {
+                expected: semester.course_code.len(),
+                got: grades.len(),
+            });
+        }
+        let mut courses = Vec::with_capacity(grades.len());
+        for (index, grade) in grades.iter().enumerate() {
+            let points = scale.points(grade).ok_or_else(|| CalcError::InvalidGrade {
+                index,
+                value: grade.clone(),
+            })?;
+            courses.push(ExportCourse {
+                code: semester.course_code[index].clone(),
+                name: semester.course_name[index].clone(),
+                credit: semester.course_credit[index],
+                grade: grade.trim().to_uppercase(),
+                points,
+                grade_points: semester.course_credit[index] * points,
+                repeat: false,
+            });
+        }
+        Ok(ExportSemester {
+            semester: semester.id.number,
+            track: semester.id.track,
+            credits: semester.total_credit,
+            spi,
+            cpi,
+            courses,
+        })
+    }
+
+    // One semester of a calculated transcript, repeats included.
+    pub fn from_result(result: &SemesterResult, scale: &GradeScale) -> Self {
+        let courses = result
+            .courses
+            .iter()
+            .map(|c| {
+                // Transcript grades were validated when the SPI was worked out
+                let points = scale.points(&c.grade).unwrap_or(0.0);
+                ExportCourse {
+                    code: c.code.clone(),
+                    name: c.name.clone(),
+                    credit: c.credit,
+                    grade: c.grade.trim().to_uppercase(),
+                    points,
+                    grade_points: c.credit * points,
+                    repeat: c.repeat,
+                }
+            })
+            .collect();
+        ExportSemester {
+            semester: result.semester.number,
+            track: result.semester.track,
+            credits: result.credits,
+            spi: result.spi,
+            cpi: Some(result.cpi),
+            courses,
+        }
+    }
+}
+
+pub fn to_json(export: &Export) -> String {
+    serde_json::to_string_pretty(export).unwrap_or_default()
+}
+
+// An f32 as the JSON number it prints as, e.g. 8.2745 rather than the
+// 8.274499893188477 a plain widening to f64 gives. For JSON built by hand;
+// derived Serialize impls already print f32 this way.
+pub fn json_number(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(f64::NAN)
+}
+
+// One row per course, the semester columns repeated on each. A semester with
+// no course rows still gets one row for its SPI and CPI.
+pub fn to_csv(export: &Export) -> String {
+    let mut out = String::from("programme,batch,semester,track,code,name,credit,grade,points,grade_points,repeat,semester_credits,spi,cpi\n");
+    for sem in &export.semesters {
+        let track = sem.track.map_or("", |t| t.id());
+        let cpi = sem.cpi.map_or(String::new(), |cpi| cpi.to_string());
+        let mut row = |course: Option<&ExportCourse>| {
+            let course_fields = match course {
+                Some(c) => [
+                    csv_field(&c.code),
+                    csv_field(&c.name),
+                    c.credit.to_string(),
+                    csv_field(&c.grade),
+                    c.points.to_string(),
+                    c.grade_points.to_string(),
+                    c.repeat.to_string(),
+                ],
+                None => Default::default(),
+            };
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{},{}",
+                csv_field(&export.programme),
+                export.batch,
+                sem.semester,
+                track,
+                course_fields.join(","),
+                sem.credits,
+                sem.spi,
+                cpi
+            );
+        };
+        if sem.courses.is_empty() {
+            row(None);
+        }
+        for course in &sem.courses {
+            row(Some(course));
+        }
+    }
+    out
+}
+
+// Quotes a field that holds a comma, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grades;
+    use crate::semdata::SemesterId;
+
+    fn semester() -> Semester {
+        Semester {
+            id: SemesterId::new(2),
+            course_code: vec!["MA102".to_string(), "HS102".to_string()],
+            course_name: vec![
+                "Probability, Statistics".to_string(),
+                "The \"Soft\" Skills".to_string(),
+            ],
+            course_credit: vec![8.0, 4.0],
+            total_credit: 12.0,
+        }
+    }
+
+    #[test]
+    fn csv_has_a_row_per_course_with_quoted_names() {
+        let entered = vec!["ab".to_string(), "BB".to_string()];
+        let sem = ExportSemester::from_grades(
+            &semester(),
+            &entered,
+            grades::default_scale(),
+            8.667,
+            Some(8.5),
+        )
+        .unwrap();
+        let export = Export {
+            programme: "cse".to_string(),
+            batch: 2021,
+            semesters: vec![sem],
+        };
+        let csv = to_csv(&export);
+        let lines: Vec<&str> = csv.lines().skip(1).collect();
+        assert_eq!(
+            lines,
+            [
+                "cse,2021,2,,MA102,\"Probability, Statistics\",8,AB,9,72,false,12,8.667,8.5",
+                "cse,2021,2,,HS102,\"The \"\"Soft\"\" Skills\",4,BB,8,32,false,12,8.667,8.5",
+            ]
+        );
+    }
+
+    #[test]
+    fn spi_only_semester_exports_one_row() {
+        let sem = ExportSemester::from_grades(&semester(), &[], grades::default_scale(), 9.0, None)
+            .unwrap();
+        let export = Export {
+            programme: "cse".to_string(),
+            batch: 2021,
+            semesters: vec![sem],
+        };
+        assert_eq!(
+            to_csv(&export).lines().nth(1),
+            Some("cse,2021,2,,,,,,,,,12,9,")
+        );
+        assert!(ExportSemester::from_grades(
+            &semester(),
+            &["AA".to_string()],
+            grades::default_scale(),
+            9.0,
+            None
+        )
+        .is_err());
+    }
+}
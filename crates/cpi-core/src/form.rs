@@ -0,0 +1,416 @@
+// The single-semester SPI/CPI form every frontend shows: inputs as entered,
+// checked and turned into results the same way everywhere. Frontends only lay
+// out the fields and word the errors.
+
+use crate::functions::{self, CalcError};
+use crate::grades::GradeScale;
+use crate::semdata::{self, Curriculum, SemesterId, Track};
+use std::collections::BTreeMap;
+
+// Inputs of one SPI or CPI calculation, borrowed from a frontend's state.
+pub struct SemesterForm<'a> {
+    pub programme: &'a str,
+    pub batch: u16,
+    pub semester: SemesterId,
+    pub grades: &'a [String],
+    pub electives: &'a [Option<String>], // Per course slot, like the grades
+    pub cpi: bool,                       // SPI alone when false
+    pub cpi_option: Option<i32>,         // 1: previous CPI and grades, 2: previous CPI and SPI
+    pub prev_cpi: &'a str,               // As typed
+    pub current_spi: &'a str,            // As typed; Option 2 only
+    pub path_tracks: &'a BTreeMap<u8, Track>, // Tracks taken in earlier semesters
+    pub path_electives: &'a BTreeMap<u8, Vec<Option<String>>>, // Electives taken in them, per course slot
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormResult {
+    pub spi: f32,         // Calculated, or as entered for Option 2
+    pub cpi: Option<f32>, // For CPI calculations only
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormError {
+    Grades(Vec<(usize, CalcError)>), // Every course whose grade is missing or not on the scale
+    PrevCpi(String),                 // Previous CPI as typed: blank or not a number
+    CurrentSpi(String),              // SPI as typed for Option 2: blank or not a number
+    NoCpiOption,
+    Calc(CalcError),
+}
+
+impl SemesterForm<'_> {
+    // Whether the course grades go into the result: always for SPI, and for
+    // CPI in semester 1 and with Option 1.
+    pub fn needs_grades(&self) -> bool {
+        !self.cpi || self.semester.number == 1 || self.cpi_option == Some(1)
+    }
+
+    pub fn calculate(&self, scale: &GradeScale) -> Result<FormResult, FormError> {
+        let spi = if self.needs_grades() {
+            let errors = grade_errors(self.grades, scale);
+            if !errors.is_empty() {
+                return Err(FormError::Grades(errors));
+            }
+            let spi = functions::calc_spi(
+                self.programme,
+                self.batch,
+                self.semester,
+                self.electives,
+                self.grades.to_vec(),
+                scale,
+            )
+            .map_err(FormError::Calc)?;
+            Some(spi)
+        } else {
+            None
+        };
+
+        let prev_cpi =
+            || number(self.prev_cpi).ok_or_else(|| FormError::PrevCpi(self.prev_cpi.to_string()));
+        let (prev_cpi, spi) = match spi {
+            Some(spi) if !self.cpi => return Ok(FormResult { spi, cpi: None }),
+            // CPI after semester 1 is just its SPI
+            Some(spi) if self.semester.number == 1 => {
+                return Ok(FormResult {
+                    spi,
+                    cpi: Some(spi),
+                })
+            }
+            Some(spi) => (prev_cpi()?, spi),
+            None if self.cpi_option == Some(2) => {
+                let prev_cpi = prev_cpi()?;
+                let spi = number(self.current_spi)
+                    .ok_or_else(|| FormError::CurrentSpi(self.current_spi.to_string()))?;
+                (prev_cpi, spi)
+            }
+            None => return Err(FormError::NoCpiOption),
+        };
+
+        let path = match semdata::get_curriculum(self.programme, self.batch) {
+            Some(curriculum) => path_before(curriculum, self.semester.number, self.path_tracks),
+            None => Vec::new(), // The calculation reports the missing curriculum
+        };
+        let cpi = functions::calculate_cpi_option3(
+            self.programme,
+            self.batch,
+            self.semester,
+            self.electives,
+            &path,
+            &path_electives(&path, self.path_electives),
+            prev_cpi,
+            spi,
+        )
+        .map_err(FormError::Calc)?;
+        Ok(FormResult {
+            spi,
+            cpi: Some(cpi),
+        })
+    }
+}
+
+// Semesters 1 to `number - 1`, along the tracks picked for them. A track the
+// semester does not offer (e.g. left over from another curriculum) counts as
+// not picked.
+pub fn path_before(
+    curriculum: &Curriculum,
+    number: u8,
+    tracks: &BTreeMap<u8, Track>,
+) -> Vec<SemesterId> {
+    (1..number)
+        .map(|n| SemesterId {
+            number: n,
+            track: tracks
+                .get(&n)
+                .copied()
+                .filter(|t| curriculum.tracks(n).contains(t)),
+        })
+        .collect()
+}
+
+// The electives chosen in each semester of `path`, in path order, as
+// `functions::path_credits` takes them. Semesters without any keep their
+// placeholder credits.
+pub fn path_electives(
+    path: &[SemesterId],
+    chosen: &BTreeMap<u8, Vec<Option<String>>>,
+) -> Vec<Vec<Option<String>>> {
+    path.iter()
+        .map(|id| chosen.get(&id.number).cloned().unwrap_or_default())
+        .collect()
+}
+
+// The elective chosen for course slot `index` of earlier semester `number`.
+pub fn path_elective(
+    chosen: &BTreeMap<u8, Vec<Option<String>>>,
+    number: u8,
+    index: usize,
+) -> Option<String> {
+    chosen.get(&number)?.get(index)?.clone()
+}
+
+// Records `code` (None to clear it) as the elective taken in course slot
+// `index` of earlier semester `number`.
+pub fn choose_path_elective(
+    chosen: &mut BTreeMap<u8, Vec<Option<String>>>,
+    number: u8,
+    index: usize,
+    code: Option<String>,
+) {
+    let slots = chosen.entry(number).or_default();
+    if slots.len() <= index {
+        slots.resize(index + 1, None);
+    }
+    slots[index] = code;
+}
+
+// The courses whose grade is missing or not on `scale`, by course index.
+pub fn grade_errors(grades: &[String], scale: &GradeScale) -> Vec<(usize, CalcError)> {
+    grades
+        .iter()
+        .enumerate()
+        .filter_map(|(i, grade)| {
+            functions::grade_points(i, grade, scale)
+                .err()
+                .map(|err| (i, err))
+        })
+        .collect()
+}
+
+// The message shown next to a course row for an error that names one.
+pub fn course_message(err: &CalcError) -> String {
+    match err {
+        CalcError::MissingGrade { .. } => "Please select a grade.".to_string(),
+        CalcError::InvalidGrade { value, .. } => format!("'{}' is not a valid grade.", value),
+        CalcError::InvalidRepeatGrade { value, .. } if value.trim().is_empty() => {
+            "Please select a grade.".to_string()
+        }
+        CalcError::InvalidRepeatGrade { value, .. } => format!("'{}' is not a valid grade.", value),
+        CalcError::NotABacklog(_) => "This course is not an outstanding backlog.".to_string(),
+        CalcError::InvalidElective { code, .. } => {
+            format!("{} cannot fill this slot (is it chosen twice?).", code)
+        }
+        other => format!("{}.", other),
+    }
+}
+
+// The message shown for a failed calculation as a whole.
+pub fn error_message(err: &CalcError) -> String {
+    match err {
+        CalcError::TrackNotChosen(number) => {
+            format!("Please select the track taken in Semester {}.", number)
+        }
+        err => format!("Could not calculate: {}.", err),
+    }
+}
+
+fn number(value: &str) -> Option<f32> {
+    value.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grades::default_scale;
+
+    const BATCH: u16 = 2021;
+
+    fn grades(letters: &[&str]) -> Vec<String> {
+        letters.iter().map(|g| g.to_string()).collect()
+    }
+
+    const NO_ELECTIVES: &BTreeMap<u8, Vec<Option<String>>> = &BTreeMap::new();
+
+    fn form<'a>(
+        semester: SemesterId,
+        grades: &'a [String],
+        path_tracks: &'a BTreeMap<u8, Track>,
+    ) -> SemesterForm<'a> {
+        SemesterForm {
+            programme: "cse",
+            batch: BATCH,
+            semester,
+            grades,
+            electives: &[],
+            cpi: true,
+            cpi_option: None,
+            prev_cpi: "",
+            current_spi: "",
+            path_tracks,
+            path_electives: NO_ELECTIVES,
+        }
+    }
+
+    #[test]
+    fn spi_alone_comes_from_the_grades() {
+        let letters = grades(&["AA"; 8]);
+        let tracks = BTreeMap::new();
+        let spi = SemesterForm {
+            cpi: false,
+            ..form(SemesterId::new(1), &letters, &tracks)
+        };
+        assert_eq!(
+            spi.calculate(default_scale()),
+            Ok(FormResult {
+                spi: 10.0,
+                cpi: None
+            })
+        );
+    }
+
+    #[test]
+    fn first_semester_cpi_is_its_spi() {
+        let letters = grades(&["BB"; 8]);
+        let tracks = BTreeMap::new();
+        let result = form(SemesterId::new(1), &letters, &tracks)
+            .calculate(default_scale())
+            .unwrap();
+        assert_eq!(result.cpi, Some(8.0));
+    }
+
+    // Semester 1 carries 46 credits and semester 2 another 44
+    #[test]
+    fn options_weigh_the_previous_cpi() {
+        let letters = grades(&["AA"; 7]);
+        let tracks = BTreeMap::new();
+        let option1 = SemesterForm {
+            cpi_option: Some(1),
+            prev_cpi: " 8 ",
+            ..form(SemesterId::new(2), &letters, &tracks)
+        };
+        let result = option1.calculate(default_scale()).unwrap();
+        assert_eq!(result.spi, 10.0);
+        // (46 * 8 + 44 * 10) / 90
+        assert!((result.cpi.unwrap() - 808.0 / 90.0).abs() < 1e-5);
+
+        // Option 2 takes the SPI as entered and ignores the grades
+        let option2 = SemesterForm {
+            cpi_option: Some(2),
+            prev_cpi: "8",
+            current_spi: "10",
+            grades: &[],
+            ..option1
+        };
+        assert_eq!(option2.calculate(default_scale()), Ok(result));
+    }
+
+    #[test]
+    fn input_problems_are_reported() {
+        let mut letters = grades(&["AA"; 7]);
+        letters[1].clear();
+        letters[4] = "ZZ".to_string();
+        let tracks = BTreeMap::new();
+        let base = form(SemesterId::new(2), &letters, &tracks);
+        assert_eq!(base.calculate(default_scale()), Err(FormError::NoCpiOption));
+
+        let option1 = SemesterForm {
+            cpi_option: Some(1),
+            ..base
+        };
+        match option1.calculate(default_scale()) {
+            Err(FormError::Grades(errors)) => {
+                let rows: Vec<usize> = errors.iter().map(|(i, _)| *i).collect();
+                assert_eq!(rows, [1, 4]);
+                assert_eq!(course_message(&errors[0].1), "Please select a grade.");
+                assert_eq!(course_message(&errors[1].1), "'ZZ' is not a valid grade.");
+            }
+            other => panic!("expected grade errors, got {:?}", other),
+        }
+
+        let option2 = SemesterForm {
+            cpi_option: Some(2),
+            prev_cpi: "8.5",
+            current_spi: "nine",
+            ..option1
+        };
+        assert_eq!(
+            option2.calculate(default_scale()),
+            Err(FormError::CurrentSpi("nine".to_string()))
+        );
+        let blank = SemesterForm {
+            prev_cpi: "",
+            ..option2
+        };
+        assert_eq!(
+            blank.calculate(default_scale()),
+            Err(FormError::PrevCpi(String::new()))
+        );
+    }
+
+    #[test]
+    fn earlier_tracks_come_from_the_path() {
+        let eighth = SemesterId::with_track(8, Track::Project);
+        let no_tracks = BTreeMap::new();
+        let option2 = SemesterForm {
+            cpi_option: Some(2),
+            prev_cpi: "8",
+            current_spi: "9",
+            ..form(eighth, &[], &no_tracks)
+        };
+        let err = option2.calculate(default_scale()).unwrap_err();
+        assert_eq!(err, FormError::Calc(CalcError::TrackNotChosen(7)));
+        assert_eq!(
+            error_message(&CalcError::TrackNotChosen(7)),
+            "Please select the track taken in Semester 7."
+        );
+
+        let tracks = BTreeMap::from([(7, Track::Project)]);
+        let with_track = SemesterForm {
+            path_tracks: &tracks,
+            ..option2
+        };
+        let result = with_track.calculate(default_scale()).unwrap();
+        // (8 * 292 + 9 * 30) / 322
+        assert!((result.cpi.unwrap() - 2606.0 / 322.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn earlier_electives_weigh_the_previous_cpi() {
+        let eighth = SemesterId::with_track(8, Track::Project);
+        let tracks = BTreeMap::from([(7, Track::Project)]);
+        // CS441 carries 8 credits against the 6 of semester 7's Elective I slot
+        let electives = BTreeMap::from([(7, vec![None, None, Some("CS441".to_string())])]);
+        let option2 = SemesterForm {
+            cpi_option: Some(2),
+            prev_cpi: "8",
+            current_spi: "9",
+            path_electives: &electives,
+            ..form(eighth, &[], &tracks)
+        };
+        let result = option2.calculate(default_scale()).unwrap();
+        // (8 * 294 + 9 * 30) / 324
+        assert!((result.cpi.unwrap() - 2622.0 / 324.0).abs() < 1e-5);
+
+        let mut chosen = BTreeMap::new();
+        choose_path_elective(&mut chosen, 7, 2, Some("CS441".to_string()));
+        assert_eq!(chosen, electives);
+        assert_eq!(path_elective(&chosen, 7, 2).as_deref(), Some("CS441"));
+        assert_eq!(path_elective(&chosen, 7, 4), None);
+
+        // An elective the slot does not take is reported, not left out
+        let wrong = BTreeMap::from([(7, vec![None, None, Some("HS201".to_string())])]);
+        let err = SemesterForm {
+            path_electives: &wrong,
+            ..option2
+        }
+        .calculate(default_scale())
+        .unwrap_err();
+        match err {
+            FormError::Calc(err) => assert_eq!(err.kind(), "invalid_elective"),
+            other => panic!("expected an invalid elective, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn tracks_a_semester_does_not_offer_are_dropped() {
+        let cse = semdata::get_curriculum("cse", BATCH).unwrap();
+        let tracks = BTreeMap::from([(3, Track::Project), (7, Track::Internship)]);
+        let path = path_before(cse, 8, &tracks);
+        assert_eq!(path.len(), 7);
+        assert!(path.iter().all(|id| id.track.is_none()));
+
+        let tracks = BTreeMap::from([(7, Track::Electives)]);
+        assert_eq!(
+            path_before(cse, 8, &tracks)[6],
+            SemesterId::with_track(7, Track::Electives)
+        );
+    }
+}
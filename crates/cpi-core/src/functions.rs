@@ -0,0 +1,1185 @@
+use crate::grades::GradeScale;
+use crate::semdata::{self, Curriculum, Semester, SemesterId};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Why a calculation could not be carried out. Course indices are positions in
+// the semester's course list, starting at 0.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+    UnknownProgramme(String),
+    NoCurricula(String), // Programme without any curriculum to default the batch to
+    UnknownCurriculum {
+        programme: String,
+        batch: u16,
+    },
+    UnknownSemester(SemesterId),
+    TrackNotChosen(u8), // Semester has tracks but the path names none
+    IncompletePath {
+        expected: u8,
+        got: usize,
+    }, // Path does not cover every earlier semester
+    GradeCountMismatch {
+        expected: usize,
+        got: usize,
+    },
+    MissingGrade {
+        index: usize,
+    },
+    InvalidGrade {
+        index: usize,
+        value: String,
+    },
+    InvalidElective {
+        index: usize,
+        code: String,
+    }, // Not a catalog course for that slot
+    ZeroCredits(SemesterId),
+    InvalidCpi(f32),
+    InvalidSpi(f32),
+    OutOfOrder {
+        expected: u8,
+        got: SemesterId,
+    },
+    TargetNotAfterCurrent {
+        current: SemesterId,
+        target: SemesterId,
+    },
+    NotABacklog(CourseRef), // Repeated course that is not an outstanding fail
+    InvalidRepeatGrade {
+        course: CourseRef,
+        value: String,
+    },
+    // An error in one semester of a transcript
+    InSemester(SemesterId, Box<CalcError>),
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::UnknownProgramme(programme) => {
+                write!(f, "unknown programme '{}'", programme)
+            }
+            CalcError::NoCurricula(programme) => {
+                write!(f, "programme '{}' has no curricula", programme)
+            }
+            CalcError::UnknownCurriculum { programme, batch } => write!(
+                f,
+                "no curriculum for programme '{}' and batch {}",
+                programme, batch
+            ),
+            CalcError::UnknownSemester(id) => write!(f, "no data for semester {}", id),
+            CalcError::TrackNotChosen(number) => {
+                write!(f, "no track chosen for semester {}", number)
+            }
+            CalcError::IncompletePath { expected, got } => {
+                write!(f, "expected {} earlier semesters, got {}", expected, got)
+            }
+            CalcError::GradeCountMismatch { expected, got } => {
+                write!(f, "expected {} grades, got {}", expected, got)
+            }
+            CalcError::MissingGrade { index } => write!(f, "course {} has no grade", index + 1),
+            CalcError::InvalidGrade { index, value } => {
+                write!(f, "course {} has invalid grade '{}'", index + 1, value)
+            }
+            CalcError::InvalidElective { index, code } => write!(
+                f,
+                "course {} cannot be filled by elective '{}'",
+                index + 1,
+                code
+            ),
+            CalcError::ZeroCredits(id) => write!(f, "semester {} has no credits", id),
+            CalcError::InvalidCpi(cpi) => write!(f, "CPI {} is outside 0-10", cpi),
+            CalcError::InvalidSpi(spi) => write!(f, "SPI {} is outside 0-10", spi),
+            CalcError::OutOfOrder { expected, got } => {
+                write!(f, "expected semester {}, got semester {}", expected, got)
+            }
+            CalcError::TargetNotAfterCurrent { current, target } => write!(
+                f,
+                "semester {} does not come after semester {}",
+                target, current
+            ),
+            CalcError::NotABacklog(course) => write!(f, "{} is not an outstanding backlog", course),
+            CalcError::InvalidRepeatGrade { course, value } if value.trim().is_empty() => {
+                write!(f, "repeat of {} has no grade", course)
+            }
+            CalcError::InvalidRepeatGrade { course, value } => {
+                write!(f, "repeat of {} has invalid grade '{}'", course, value)
+            }
+            CalcError::InSemester(id, err) => write!(f, "semester {}: {}", id, err),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+impl CalcError {
+    // Stable snake_case name of the error, for API clients to match on.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CalcError::UnknownProgramme(_) => "unknown_programme",
+            CalcError::NoCurricula(_) | CalcError::UnknownCurriculum { .. } => "unknown_curriculum",
+            CalcError::UnknownSemester(_) => "unknown_semester",
+            CalcError::TrackNotChosen(_) => "track_not_chosen",
+            CalcError::IncompletePath { .. } => "incomplete_path",
+            CalcError::GradeCountMismatch { .. } => "grade_count_mismatch",
+            CalcError::MissingGrade { .. } => "missing_grade",
+            CalcError::InvalidGrade { .. } => "invalid_grade",
+            CalcError::InvalidElective { .. } => "invalid_elective",
+            CalcError::ZeroCredits(_) => "zero_credits",
+            CalcError::InvalidCpi(_) => "invalid_cpi",
+            CalcError::InvalidSpi(_) => "invalid_spi",
+            CalcError::OutOfOrder { .. } => "out_of_order",
+            CalcError::TargetNotAfterCurrent { .. } => "target_not_after_current",
+            CalcError::NotABacklog(_) => "not_a_backlog",
+            CalcError::InvalidRepeatGrade { .. } => "invalid_repeat_grade",
+            CalcError::InSemester(_, err) => err.kind(),
+        }
+    }
+
+    // Index of the course at fault, when the error names one.
+    pub fn course(&self) -> Option<usize> {
+        match self {
+            CalcError::MissingGrade { index }
+            | CalcError::InvalidGrade { index, .. }
+            | CalcError::InvalidElective { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+// `batch`, or the newest batch up to `latest` that `programme` has a
+// curriculum for.
+pub fn batch_for(programme: &str, batch: Option<u16>, latest: u16) -> Result<u16, CalcError> {
+    let found = semdata::get_programme(programme)
+        .ok_or_else(|| CalcError::UnknownProgramme(programme.to_string()))?;
+    batch
+        .or_else(|| found.newest_batch(latest))
+        .ok_or_else(|| CalcError::NoCurricula(programme.to_string()))
+}
+
+fn curriculum(programme: &str, batch: u16) -> Result<&'static Curriculum, CalcError> {
+    semdata::get_curriculum(programme, batch).ok_or_else(|| CalcError::UnknownCurriculum {
+        programme: programme.to_string(),
+        batch,
+    })
+}
+
+fn semester(
+    programme: &str,
+    batch: u16,
+    sem_id: SemesterId,
+    electives: &[Option<String>],
+) -> Result<Semester, CalcError> {
+    resolve_semester(curriculum(programme, batch)?, sem_id, electives)
+}
+
+// Semester `sem_id` with its placeholder slots bound to catalog electives.
+// `electives` holds an optional elective code per course, in course order;
+// None (or a short list) keeps the placeholder. `total_credit` follows the
+// chosen courses' credits.
+pub fn resolve_semester(
+    curriculum: &Curriculum,
+    sem_id: SemesterId,
+    electives: &[Option<String>],
+) -> Result<Semester, CalcError> {
+    let mut semester = curriculum
+        .semester(sem_id)
+        .cloned()
+        .ok_or(CalcError::UnknownSemester(sem_id))?;
+    for (index, code) in electives.iter().enumerate() {
+        let code = match code {
+            Some(code) => code,
+            None => continue,
+        };
+        let invalid = || CalcError::InvalidElective {
+            index,
+            code: code.clone(),
+        };
+        let elective = curriculum
+            .electives_for(sem_id, index)
+            .into_iter()
+            .find(|e| e.code.eq_ignore_ascii_case(code.trim()))
+            .ok_or_else(invalid)?;
+        // The same course cannot fill two slots
+        if semester.course_code[..index].contains(&elective.code) {
+            return Err(invalid());
+        }
+        let delta = elective.credit - semester.course_credit[index];
+        semester.course_code[index] = elective.code.clone();
+        semester.course_name[index] = elective.name.clone();
+        semester.course_credit[index] = elective.credit;
+        semester.total_credit += delta;
+    }
+    Ok(semester)
+}
+
+// Points for the letter grade of the course at `index`.
+pub fn grade_points(index: usize, grade: &str, scale: &GradeScale) -> Result<f32, CalcError> {
+    if grade.trim().is_empty() {
+        return Err(CalcError::MissingGrade { index });
+    }
+    scale.points(grade).ok_or_else(|| CalcError::InvalidGrade {
+        index,
+        value: grade.to_string(),
+    })
+}
+
+// Credits earned along `path`, the semesters actually taken from semester 1
+// on, in order. Semesters 7 and 8 count the credits of the track named for
+// them, so Option 1 in one and Option 3 in the other sum correctly.
+// `electives` holds the elective choices of each path semester, in path order,
+// as `resolve_semester` takes them; a semester without any counts its slots at
+// their placeholder credits.
+pub fn path_credits(
+    curriculum: &Curriculum,
+    path: &[SemesterId],
+    electives: &[Vec<Option<String>>],
+) -> Result<f32, CalcError> {
+    let mut credits = 0.0;
+    for (i, &id) in path.iter().enumerate() {
+        if id.number as usize != i + 1 {
+            return Err(CalcError::OutOfOrder {
+                expected: i as u8 + 1,
+                got: id,
+            });
+        }
+        if id.track.is_none() && !curriculum.tracks(id.number).is_empty() {
+            return Err(CalcError::TrackNotChosen(id.number));
+        }
+        let chosen = electives.get(i).map_or(&[][..], Vec::as_slice);
+        if curriculum.semester(id).is_none() {
+            return Err(CalcError::UnknownSemester(id));
+        }
+        credits += resolve_semester(curriculum, id, chosen)
+            .map_err(|err| CalcError::InSemester(id, Box::new(err)))?
+            .total_credit;
+    }
+    Ok(credits)
+}
+
+pub fn calculate_spi_n(
+    programme: &str,
+    batch: u16,
+    sem_id: SemesterId,
+    electives: &[Option<String>],
+    grades: Vec<f32>,
+) -> Result<f32, CalcError> {
+    let semesters = semester(programme, batch, sem_id, electives)?;
+    if grades.len() != semesters.course_credit.len() {
+        return Err(CalcError::GradeCountMismatch {
+            expected: semesters.course_credit.len(),
+            got: grades.len(),
+        });
+    }
+    let total_credit = semesters.total_credit;
+    if total_credit == 0.0 {
+        return Err(CalcError::ZeroCredits(sem_id)); // Avoid division by zero
+    }
+
+    let mut spi = 0.0;
+    for (i, (grade, credit)) in grades.iter().zip(&semesters.course_credit).enumerate() {
+        if !(0.0..=10.0).contains(grade) {
+            return Err(CalcError::InvalidGrade {
+                index: i,
+                value: grade.to_string(),
+            });
+        }
+        spi += grade * credit;
+    }
+    Ok(spi / total_credit)
+}
+
+// Grades are letter grades (AA, AB, ...), converted to points through `scale`.
+pub fn calc_spi(
+    programme: &str,
+    batch: u16,
+    sem_id: SemesterId,
+    electives: &[Option<String>],
+    grades: Vec<String>,
+    scale: &GradeScale,
+) -> Result<f32, CalcError> {
+    // Ensure the number of grades matches the expected number for the semester
+    let sem_info = semester(programme, batch, sem_id, electives)?;
+    if grades.len() != sem_info.course_code.len() {
+        return Err(CalcError::GradeCountMismatch {
+            expected: sem_info.course_code.len(),
+            got: grades.len(),
+        });
+    }
+
+    let grades_recieved_by_student = grades
+        .iter()
+        .enumerate()
+        .map(|(i, grade)| grade_points(i, grade, scale))
+        .collect::<Result<Vec<f32>, CalcError>>()?;
+
+    calculate_spi_n(
+        programme,
+        batch,
+        sem_id,
+        electives,
+        grades_recieved_by_student,
+    )
+}
+
+// `path` lists the semesters taken before `x`, from semester 1 on, and
+// `path_electives` their elective choices (see `path_credits`); their credits
+// weight the previous CPI.
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_cpi_option3(
+    programme: &str,
+    batch: u16,
+    x: SemesterId,
+    electives: &[Option<String>],
+    path: &[SemesterId],
+    path_electives: &[Vec<Option<String>>],
+    cpi_of_xminus1: f32,
+    spi_of_x: f32,
+) -> Result<f32, CalcError> {
+    if !(0.0..=10.0).contains(&cpi_of_xminus1) {
+        return Err(CalcError::InvalidCpi(cpi_of_xminus1));
+    }
+    if !(0.0..=10.0).contains(&spi_of_x) {
+        return Err(CalcError::InvalidSpi(spi_of_x));
+    }
+    let curriculum = curriculum(programme, batch)?;
+    let semester_x = resolve_semester(curriculum, x, electives)?;
+    // CPI after semester 1 is just its SPI
+    if x.number <= 1 {
+        return Ok(spi_of_x);
+    }
+    if path.len() != x.number as usize - 1 {
+        return Err(CalcError::IncompletePath {
+            expected: x.number - 1,
+            got: path.len(),
+        });
+    }
+
+    let credit_of_sem_x: f32 = semester_x.total_credit;
+    let cum_sum_of_credit_till_sem_x_minus_1: f32 = path_credits(curriculum, path, path_electives)?;
+
+    let total_cumulative_credits = cum_sum_of_credit_till_sem_x_minus_1 + credit_of_sem_x;
+
+    // Avoid division by zero
+    if total_cumulative_credits == 0.0 {
+        return Err(CalcError::ZeroCredits(x));
+    }
+
+    Ok(
+        (cpi_of_xminus1 * cum_sum_of_credit_till_sem_x_minus_1 + spi_of_x * credit_of_sem_x)
+            / total_cumulative_credits,
+    )
+}
+
+// A course by where it sits in the curriculum: its semester number and its
+// position in that semester's course list. Codes are not unique (HSXXX and
+// friends repeat), so backlogs are tracked by position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CourseRef {
+    pub semester: u8,
+    pub course: usize,
+}
+
+impl fmt::Display for CourseRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "semester {} course {}", self.semester, self.course + 1)
+    }
+}
+
+// A backlog course registered again, with the grade from the new attempt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Repeat {
+    pub course: CourseRef,
+    pub grade: String,
+}
+
+// How a cleared backlog counts towards CPI. Either way the new attempt counts
+// in the SPI of the semester it was taken in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatPolicy {
+    // Only the latest attempt counts; the failed credits drop out of CPI
+    #[default]
+    Replace,
+    // Every attempt counts, so the failed credits stay in CPI at zero points
+    Average,
+}
+
+impl RepeatPolicy {
+    pub const ALL: [RepeatPolicy; 2] = [RepeatPolicy::Replace, RepeatPolicy::Average];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            RepeatPolicy::Replace => "replace",
+            RepeatPolicy::Average => "average",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RepeatPolicy::Replace => "Replace (new grade replaces the FF)",
+            RepeatPolicy::Average => "Average (FF credits still count)",
+        }
+    }
+}
+
+impl FromStr for RepeatPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RepeatPolicy::ALL
+            .into_iter()
+            .find(|p| p.id().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown repeat policy '{}'", s))
+    }
+}
+
+// One semester of a transcript: which semester it was, the letter grade for
+// each of its courses in curriculum order, the electives chosen for its
+// placeholder slots (see `resolve_semester`) and any backlogs retaken in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptEntry {
+    pub semester: SemesterId,
+    pub grades: Vec<String>,
+    pub electives: Vec<Option<String>>,
+    pub repeats: Vec<Repeat>,
+}
+
+// A failed course waiting to be cleared, named as it was taken.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backlog {
+    pub course: CourseRef,
+    pub code: String,
+    pub name: String,
+    pub credit: f32,
+}
+
+// A course as it appears on a transcript.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseGrade {
+    pub code: String,
+    pub name: String,
+    pub credit: f32,
+    pub grade: String,
+    pub repeat: bool, // A backlog retaken this semester
+}
+
+// Per-semester outcome of a transcript calculation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemesterResult {
+    pub semester: SemesterId,
+    pub courses: Vec<CourseGrade>,
+    pub credits: f32,
+    pub grade_points: f32, // Sum of credit * points over the semester's courses
+    pub spi: f32,
+    pub cumulative_credits: f32,
+    pub cpi: f32,
+    pub backlogs: Vec<Backlog>, // Failed courses not yet cleared after this semester
+}
+
+// Courses of `semester` failed according to `grades`.
+fn failed_courses(semester: &Semester, grades: &[String], scale: &GradeScale) -> Vec<Backlog> {
+    grades
+        .iter()
+        .enumerate()
+        .filter(|(course, grade)| *course < semester.course_code.len() && scale.is_fail(grade))
+        .map(|(course, _)| Backlog {
+            course: CourseRef {
+                semester: semester.id.number,
+                course,
+            },
+            code: semester.course_code[course].clone(),
+            name: semester.course_name[course].clone(),
+            credit: semester.course_credit[course],
+        })
+        .collect()
+}
+
+// Failed courses not cleared by a later repeat. Lenient about the rest of the
+// transcript, so editors can offer backlogs while the grades are still being
+// filled in.
+pub fn outstanding_backlogs(
+    programme: &str,
+    batch: u16,
+    transcript: &[TranscriptEntry],
+    scale: &GradeScale,
+) -> Vec<Backlog> {
+    let curriculum = match semdata::get_curriculum(programme, batch) {
+        Some(c) => c,
+        None => return Vec::new(),
+    };
+    let mut backlogs: Vec<Backlog> = Vec::new();
+    for entry in transcript {
+        for repeat in &entry.repeats {
+            if scale
+                .points(&repeat.grade)
+                .is_some_and(|points| points > 0.0)
+            {
+                backlogs.retain(|b| b.course != repeat.course);
+            }
+        }
+        // Fall back to the placeholders if an elective choice is off
+        let semester = resolve_semester(curriculum, entry.semester, &entry.electives)
+            .ok()
+            .or_else(|| curriculum.semester(entry.semester).cloned());
+        if let Some(semester) = semester {
+            backlogs.extend(failed_courses(&semester, &entry.grades, scale));
+        }
+    }
+    backlogs
+}
+
+// Computes SPI and running CPI for semesters 1..N from the raw grades.
+// CPI is taken from the summed credits and grade points of every semester so
+// far, so no rounded intermediate CPI ever feeds into the next one.
+// A failed course stays a backlog until a later semester repeats it with a
+// passing grade; `policy` decides whether the failed attempt then still counts.
+// The transcript must start at semester 1 and run consecutively; errors about
+// a single semester come back wrapped in `CalcError::InSemester`.
+pub fn calculate_transcript(
+    programme: &str,
+    batch: u16,
+    transcript: &[TranscriptEntry],
+    scale: &GradeScale,
+    policy: RepeatPolicy,
+) -> Result<Vec<SemesterResult>, CalcError> {
+    let curriculum = curriculum(programme, batch)?;
+    let mut results: Vec<SemesterResult> = Vec::with_capacity(transcript.len());
+    let mut cumulative_credits = 0.0;
+    let mut cumulative_points = 0.0;
+    let mut backlogs: Vec<Backlog> = Vec::new();
+
+    for (i, entry) in transcript.iter().enumerate() {
+        if entry.semester.number as usize != i + 1 {
+            return Err(CalcError::OutOfOrder {
+                expected: i as u8 + 1,
+                got: entry.semester,
+            });
+        }
+        let in_semester = |err: CalcError| CalcError::InSemester(entry.semester, Box::new(err));
+        if curriculum.semester(entry.semester).is_none() {
+            return Err(CalcError::UnknownSemester(entry.semester));
+        }
+        let semester =
+            resolve_semester(curriculum, entry.semester, &entry.electives).map_err(in_semester)?;
+        if entry.grades.len() != semester.course_credit.len() {
+            return Err(in_semester(CalcError::GradeCountMismatch {
+                expected: semester.course_credit.len(),
+                got: entry.grades.len(),
+            }));
+        }
+        if semester.total_credit == 0.0 {
+            return Err(CalcError::ZeroCredits(entry.semester));
+        }
+
+        let mut courses: Vec<CourseGrade> =
+            Vec::with_capacity(entry.grades.len() + entry.repeats.len());
+        let mut credits = semester.total_credit;
+        let mut grade_points = 0.0;
+        for (index, grade) in entry.grades.iter().enumerate() {
+            grade_points += self::grade_points(index, grade, scale).map_err(in_semester)?
+                * semester.course_credit[index];
+            courses.push(CourseGrade {
+                code: semester.course_code[index].clone(),
+                name: semester.course_name[index].clone(),
+                credit: semester.course_credit[index],
+                grade: grade.clone(),
+                repeat: false,
+            });
+        }
+        let mut failed = failed_courses(&semester, &entry.grades, scale);
+
+        for repeat in &entry.repeats {
+            let position = backlogs
+                .iter()
+                .position(|b| b.course == repeat.course)
+                .ok_or_else(|| in_semester(CalcError::NotABacklog(repeat.course)))?;
+            let backlog = backlogs.remove(position);
+            let points = scale.points(&repeat.grade).ok_or_else(|| {
+                in_semester(CalcError::InvalidRepeatGrade {
+                    course: backlog.course,
+                    value: repeat.grade.clone(),
+                })
+            })?;
+            credits += backlog.credit;
+            grade_points += points * backlog.credit;
+            // The earlier attempt was a fail, so dropping it only removes credits
+            if policy == RepeatPolicy::Replace {
+                cumulative_credits -= backlog.credit;
+            }
+            courses.push(CourseGrade {
+                code: backlog.code.clone(),
+                name: backlog.name.clone(),
+                credit: backlog.credit,
+                grade: repeat.grade.clone(),
+                repeat: true,
+            });
+            if points == 0.0 {
+                failed.push(backlog);
+            }
+        }
+        backlogs.extend(failed);
+        cumulative_credits += credits;
+        cumulative_points += grade_points;
+
+        results.push(SemesterResult {
+            semester: entry.semester,
+            courses,
+            credits,
+            grade_points,
+            spi: grade_points / credits,
+            cumulative_credits,
+            cpi: cumulative_points / cumulative_credits,
+            backlogs: backlogs.clone(),
+        });
+    }
+
+    Ok(results)
+}
+
+// What it takes to move from the current CPI to a target CPI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetPlan {
+    pub required_spi: f32, // Average SPI needed over the remaining semesters
+    pub remaining_credits: f32,
+    pub impossible: bool, // The target needs an SPI above 10
+}
+
+// Average SPI needed over `remaining_credits` to lift `current_cpi`, earned
+// over `credits_done`, to `target_cpi`. This is calculate_cpi_option3 solved
+// for the SPI term.
+pub fn required_average_spi(
+    current_cpi: f32,
+    credits_done: f32,
+    target_cpi: f32,
+    remaining_credits: f32,
+) -> f32 {
+    (target_cpi * (credits_done + remaining_credits) - current_cpi * credits_done)
+        / remaining_credits
+}
+
+// Plans from a known CPI after semester `current` to a target CPI after the
+// last semester of `path`, which runs from semester 1 through the target
+// along the tracks taken or planned, with `path_electives` as in
+// `path_credits`. A required SPI of zero or below means the target is
+// already secured.
+pub fn plan_target_cpi(
+    programme: &str,
+    batch: u16,
+    path: &[SemesterId],
+    path_electives: &[Vec<Option<String>>],
+    current: u8,
+    current_cpi: f32,
+    target_cpi: f32,
+) -> Result<TargetPlan, CalcError> {
+    for cpi in [current_cpi, target_cpi] {
+        if !(0.0..=10.0).contains(&cpi) {
+            return Err(CalcError::InvalidCpi(cpi));
+        }
+    }
+    let current_id = path
+        .get((current as usize).wrapping_sub(1))
+        .copied()
+        .unwrap_or(SemesterId {
+            number: current,
+            track: None,
+        });
+    let target = path.last().copied().unwrap_or(current_id);
+    if current == 0 || current as usize >= path.len() {
+        return Err(CalcError::TargetNotAfterCurrent {
+            current: current_id,
+            target,
+        });
+    }
+    let curriculum = curriculum(programme, batch)?;
+    let credits_done = path_credits(curriculum, &path[..current as usize], path_electives)?;
+    let remaining_credits = path_credits(curriculum, path, path_electives)? - credits_done;
+    if remaining_credits <= 0.0 {
+        return Err(CalcError::ZeroCredits(target));
+    }
+
+    let required_spi =
+        required_average_spi(current_cpi, credits_done, target_cpi, remaining_credits);
+    Ok(TargetPlan {
+        required_spi,
+        remaining_credits,
+        impossible: required_spi > 10.0,
+    })
+}
+
+// Effect of moving one course to a neighbouring grade on the scale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradeStep {
+    pub grade: String,
+    pub spi_delta: f32,
+    pub cpi_delta: f32,
+}
+
+// How much a single course moves SPI and CPI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseImpact {
+    pub index: usize,            // Position in the semester's course list
+    pub spi_per_point: f32,      // SPI change per grade point in this course
+    pub cpi_per_point: f32,      // CPI change per grade point in this course
+    pub up: Option<GradeStep>,   // One grade better, if there is one
+    pub down: Option<GradeStep>, // One grade worse, if there is one
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    pub spi: f32,
+    pub cpi: Option<f32>, // Needs the previous CPI from semester 2 onwards
+    pub courses: Vec<CourseImpact>,
+}
+
+impl Simulation {
+    // Courses ordered by the CPI gained per grade point, largest first.
+    pub fn ranking(&self) -> Vec<&CourseImpact> {
+        let mut ranked: Vec<&CourseImpact> = self.courses.iter().collect();
+        ranked.sort_by(|a, b| b.cpi_per_point.total_cmp(&a.cpi_per_point));
+        ranked
+    }
+}
+
+// What-if view of a semester: SPI/CPI for the expected grades and, per
+// course, the effect of a one-grade change. A course's weight is its credit
+// over the semester total for SPI and over the cumulative total for CPI, the
+// latter summed along `path`, the semesters taken before `sem_id`, with
+// `path_electives` as in `path_credits`.
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_semester(
+    programme: &str,
+    batch: u16,
+    sem_id: SemesterId,
+    electives: &[Option<String>],
+    path: &[SemesterId],
+    path_electives: &[Vec<Option<String>>],
+    grades: &[String],
+    prev_cpi: Option<f32>,
+    scale: &GradeScale,
+) -> Result<Simulation, CalcError> {
+    let semester = semester(programme, batch, sem_id, electives)?;
+    if path.len() + 1 != sem_id.number as usize {
+        return Err(CalcError::IncompletePath {
+            expected: sem_id.number.saturating_sub(1),
+            got: path.len(),
+        });
+    }
+    let credits_before = path_credits(curriculum(programme, batch)?, path, path_electives)?;
+    let points: Vec<f32> = grades
+        .iter()
+        .enumerate()
+        .map(|(i, grade)| grade_points(i, grade, scale))
+        .collect::<Result<_, _>>()?;
+
+    // Also checks the grade count and the semester's credits
+    let spi = calculate_spi_n(programme, batch, sem_id, electives, points.clone())?;
+    let cpi = if sem_id.number == 1 {
+        Some(spi)
+    } else {
+        prev_cpi
+            .map(|prev| {
+                calculate_cpi_option3(
+                    programme,
+                    batch,
+                    sem_id,
+                    electives,
+                    path,
+                    path_electives,
+                    prev,
+                    spi,
+                )
+            })
+            .transpose()?
+    };
+
+    // With the previous CPI fixed, a change in SPI moves CPI by this much
+    let cpi_per_spi = semester.total_credit / (credits_before + semester.total_credit);
+    let letters = scale.grades(); // Best first, as GradeScale checks on load
+    let step = |index: usize, target: Option<usize>| -> Option<GradeStep> {
+        let grade = letters.get(target?)?;
+        let spi_delta =
+            (grade.points - points[index]) * semester.course_credit[index] / semester.total_credit;
+        Some(GradeStep {
+            grade: grade.letter.clone(),
+            spi_delta,
+            cpi_delta: spi_delta * cpi_per_spi,
+        })
+    };
+
+    let courses = grades
+        .iter()
+        .enumerate()
+        .map(|(index, grade)| {
+            let position = letters
+                .iter()
+                .position(|g| g.letter.eq_ignore_ascii_case(grade.trim()));
+            let spi_per_point = semester.course_credit[index] / semester.total_credit;
+            CourseImpact {
+                index,
+                spi_per_point,
+                cpi_per_point: spi_per_point * cpi_per_spi,
+                up: step(index, position.and_then(|p| p.checked_sub(1))),
+                down: step(index, position.map(|p| p + 1)),
+            }
+        })
+        .collect();
+
+    Ok(Simulation { spi, cpi, courses })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grades::default_scale;
+    use crate::semdata::Track;
+
+    const BATCH: u16 = 2021;
+
+    fn grades(letters: &[&str]) -> Vec<String> {
+        letters.iter().map(|g| g.to_string()).collect()
+    }
+
+    fn entry(number: u8, letters: &[&str]) -> TranscriptEntry {
+        TranscriptEntry {
+            semester: SemesterId::new(number),
+            grades: grades(letters),
+            electives: Vec::new(),
+            repeats: Vec::new(),
+        }
+    }
+
+    fn transcript_results(
+        transcript: &[TranscriptEntry],
+        policy: RepeatPolicy,
+    ) -> Result<Vec<SemesterResult>, CalcError> {
+        calculate_transcript("cse", BATCH, transcript, default_scale(), policy)
+    }
+
+    #[test]
+    fn transcript_follows_the_bundled_curriculum() {
+        let transcript = [entry(1, &["AA"; 8]), entry(2, &["BB"; 7])];
+        let results = transcript_results(&transcript, RepeatPolicy::Replace).unwrap();
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].credits, 46.0);
+        assert_eq!(results[0].spi, 10.0);
+        assert_eq!(results[0].cpi, 10.0);
+        assert_eq!(results[0].courses[0].code, "MA101");
+
+        assert_eq!(results[1].credits, 44.0);
+        assert_eq!(results[1].spi, 8.0);
+        assert_eq!(results[1].cumulative_credits, 90.0);
+        // (46 * 10 + 44 * 8) / 90
+        assert!((results[1].cpi - 812.0 / 90.0).abs() < 1e-5);
+        assert!(results[1].backlogs.is_empty());
+    }
+
+    fn first_semesters(count: u8) -> Vec<SemesterId> {
+        (1..=count).map(SemesterId::new).collect()
+    }
+
+    // Semesters 1-2 carry 90 credits and semesters 3-4 another 86
+    #[test]
+    fn reachable_target_needs_an_spi_within_the_scale() {
+        let plan = plan_target_cpi("cse", BATCH, &first_semesters(4), &[], 2, 8.0, 8.5).unwrap();
+        assert_eq!(plan.remaining_credits, 86.0);
+        // (8.5 * 176 - 8 * 90) / 86
+        assert!((plan.required_spi - 776.0 / 86.0).abs() < 1e-5);
+        assert!(!plan.impossible);
+    }
+
+    #[test]
+    fn unreachable_target_is_flagged() {
+        let plan = plan_target_cpi("cse", BATCH, &first_semesters(4), &[], 2, 8.0, 9.0).unwrap();
+        assert!(plan.required_spi > 10.0);
+        assert!(plan.impossible);
+    }
+
+    #[test]
+    fn target_already_met_needs_no_spi() {
+        let plan = plan_target_cpi("cse", BATCH, &first_semesters(4), &[], 2, 9.0, 4.0).unwrap();
+        assert!(plan.required_spi <= 0.0);
+        assert!(!plan.impossible);
+    }
+
+    #[test]
+    fn target_must_come_after_the_current_semester() {
+        let err = plan_target_cpi("cse", BATCH, &first_semesters(2), &[], 2, 8.0, 9.0).unwrap_err();
+        assert_eq!(err.kind(), "target_not_after_current");
+    }
+
+    #[test]
+    fn simulation_steps_to_neighbouring_grades() {
+        let mut letters = ["AB"; 8];
+        letters[1] = "AA";
+        letters[2] = "FF";
+        let sim = simulate_semester(
+            "cse",
+            BATCH,
+            SemesterId::new(1),
+            &[],
+            &[],
+            &[],
+            &grades(&letters),
+            None,
+            default_scale(),
+        )
+        .unwrap();
+        assert_eq!(sim.cpi, Some(sim.spi));
+
+        // MA101, 8 of the semester's 46 credits
+        let course = &sim.courses[0];
+        let up = course.up.as_ref().unwrap();
+        let down = course.down.as_ref().unwrap();
+        assert_eq!((up.grade.as_str(), down.grade.as_str()), ("AA", "BB"));
+        assert!((up.spi_delta - 8.0 / 46.0).abs() < 1e-5);
+        assert!((down.spi_delta + 8.0 / 46.0).abs() < 1e-5);
+
+        // Nothing above the best grade or below the worst
+        assert!(sim.courses[1].up.is_none());
+        assert!(sim.courses[2].down.is_none());
+        assert_eq!(sim.courses[2].up.as_ref().unwrap().grade, "DD");
+    }
+
+    #[test]
+    fn wrong_number_of_grades_is_reported() {
+        let err = calc_spi(
+            "cse",
+            BATCH,
+            SemesterId::new(1),
+            &[],
+            grades(&["AA"; 7]),
+            default_scale(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            CalcError::GradeCountMismatch {
+                expected: 8,
+                got: 7
+            }
+        );
+        assert_eq!(err.kind(), "grade_count_mismatch");
+        assert_eq!(err.course(), None);
+    }
+
+    #[test]
+    fn unknown_grade_names_its_course() {
+        let mut letters = ["AA"; 8];
+        letters[3] = "ZZ";
+        let err = calc_spi(
+            "cse",
+            BATCH,
+            SemesterId::new(1),
+            &[],
+            grades(&letters),
+            default_scale(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), "invalid_grade");
+        assert_eq!(err.course(), Some(3));
+        assert_eq!(err.to_string(), "course 4 has invalid grade 'ZZ'");
+
+        letters[3] = " ";
+        let err = calc_spi(
+            "cse",
+            BATCH,
+            SemesterId::new(1),
+            &[],
+            grades(&letters),
+            default_scale(),
+        )
+        .unwrap_err();
+        assert_eq!((err.kind(), err.course()), ("missing_grade", Some(3)));
+    }
+
+    #[test]
+    fn unknown_semester_is_reported() {
+        let err = calc_spi(
+            "cse",
+            BATCH,
+            SemesterId::new(9),
+            &[],
+            Vec::new(),
+            default_scale(),
+        )
+        .unwrap_err();
+        assert_eq!(err, CalcError::UnknownSemester(SemesterId::new(9)));
+        assert_eq!(err.kind(), "unknown_semester");
+
+        // Semester 7 exists only per track
+        let err = calc_spi(
+            "cse",
+            BATCH,
+            SemesterId::new(7),
+            &[],
+            Vec::new(),
+            default_scale(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), "unknown_semester");
+    }
+
+    #[test]
+    fn earlier_semester_without_a_track_is_reported() {
+        let eighth = SemesterId::with_track(8, Track::Project);
+        let err = calculate_cpi_option3(
+            "cse",
+            BATCH,
+            eighth,
+            &[],
+            &first_semesters(7),
+            &[],
+            8.0,
+            9.0,
+        )
+        .unwrap_err();
+        assert_eq!(err, CalcError::TrackNotChosen(7));
+        assert_eq!(err.kind(), "track_not_chosen");
+        assert_eq!(err.to_string(), "no track chosen for semester 7");
+    }
+
+    // CS101 (6 credits) failed in semester 1 and passed with AA in semester 2,
+    // everything else AA
+    fn cleared_backlog() -> [TranscriptEntry; 2] {
+        let mut first = ["AA"; 8];
+        first[1] = "FF";
+        let mut second = entry(2, &["AA"; 7]);
+        second.repeats.push(Repeat {
+            course: CourseRef {
+                semester: 1,
+                course: 1,
+            },
+            grade: "AA".to_string(),
+        });
+        [entry(1, &first), second]
+    }
+
+    #[test]
+    fn replaced_fail_drops_out_of_cpi() {
+        let results = transcript_results(&cleared_backlog(), RepeatPolicy::Replace).unwrap();
+        assert_eq!(results[0].backlogs.len(), 1);
+        assert_eq!(results[0].backlogs[0].code, "CS101");
+        assert!(results[1].backlogs.is_empty());
+        assert_eq!(results[1].credits, 50.0);
+        assert!(results[1].courses.last().unwrap().repeat);
+        assert_eq!(results[1].cumulative_credits, 90.0);
+        assert_eq!(results[1].cpi, 10.0);
+    }
+
+    #[test]
+    fn averaged_fail_keeps_its_credits() {
+        let results = transcript_results(&cleared_backlog(), RepeatPolicy::Average).unwrap();
+        assert_eq!(results[1].spi, 10.0);
+        // 900 points over 96 credits, the failed 6 still counted
+        assert_eq!(results[1].cumulative_credits, 96.0);
+        assert_eq!(results[1].cpi, 9.375);
+    }
+
+    #[test]
+    fn repeating_a_passed_course_is_rejected() {
+        let mut transcript = cleared_backlog();
+        transcript[1].repeats[0].course.course = 0;
+        let err = transcript_results(&transcript, RepeatPolicy::Replace).unwrap_err();
+        let course = CourseRef {
+            semester: 1,
+            course: 0,
+        };
+        assert_eq!(
+            err,
+            CalcError::InSemester(SemesterId::new(2), Box::new(CalcError::NotABacklog(course)))
+        );
+        assert_eq!(err.kind(), "not_a_backlog");
+    }
+
+    // Semesters 1-6 carry 262 credits and each track of semester 7 another 30
+    fn project_path() -> Vec<SemesterId> {
+        let mut path = first_semesters(6);
+        path.push(SemesterId::with_track(7, Track::Project));
+        path
+    }
+
+    #[test]
+    fn path_credits_follow_the_track_taken() {
+        let cse = semdata::get_curriculum("cse", BATCH).unwrap();
+        assert_eq!(path_credits(cse, &project_path(), &[]), Ok(292.0));
+
+        let mut electives_path = project_path();
+        electives_path[6].track = Some(Track::Electives);
+        assert_eq!(path_credits(cse, &electives_path, &[]), Ok(292.0));
+
+        let err = path_credits(cse, &project_path()[1..], &[]).unwrap_err();
+        assert_eq!(err.kind(), "out_of_order");
+    }
+
+    #[test]
+    fn path_credits_count_bound_electives() {
+        let cse = semdata::get_curriculum("cse", BATCH).unwrap();
+        // CS441 carries 8 credits against the 6 of the Elective I slot
+        let mut electives = vec![Vec::new(); 7];
+        electives[6] = vec![None, None, Some("CS441".to_string())];
+        assert_eq!(path_credits(cse, &project_path(), &electives), Ok(294.0));
+
+        electives[6] = vec![None, None, Some("HS201".to_string())];
+        let err = path_credits(cse, &project_path(), &electives).unwrap_err();
+        assert_eq!(err.kind(), "invalid_elective");
+    }
+
+    #[test]
+    fn cpi_is_weighted_along_the_path() {
+        let eighth = SemesterId::with_track(8, Track::Project);
+        let cpi = calculate_cpi_option3("cse", BATCH, eighth, &[], &project_path(), &[], 8.0, 9.0)
+            .unwrap();
+        // (8 * 292 + 9 * 30) / 322
+        assert!((cpi - 2606.0 / 322.0).abs() < 1e-5);
+
+        let mut electives = vec![Vec::new(); 7];
+        electives[6] = vec![None, None, Some("CS441".to_string())];
+        let cpi = calculate_cpi_option3(
+            "cse",
+            BATCH,
+            eighth,
+            &[],
+            &project_path(),
+            &electives,
+            8.0,
+            9.0,
+        )
+        .unwrap();
+        // (8 * 294 + 9 * 30) / 324
+        assert!((cpi - 2622.0 / 324.0).abs() < 1e-5);
+
+        let err = calculate_cpi_option3(
+            "cse",
+            BATCH,
+            eighth,
+            &[],
+            &first_semesters(6),
+            &[],
+            8.0,
+            9.0,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            CalcError::IncompletePath {
+                expected: 7,
+                got: 6
+            }
+        );
+    }
+
+    #[test]
+    fn batch_defaults_to_the_newest_with_a_curriculum() {
+        assert_eq!(batch_for("cse", Some(2019), 2030), Ok(2019));
+        assert_eq!(batch_for("cse", None, 2030), Ok(2030));
+        let err = batch_for("xyz", None, 2030).unwrap_err();
+        assert_eq!(
+            (err.kind(), err.to_string().as_str()),
+            ("unknown_programme", "unknown programme 'xyz'")
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::semdata::CurriculumError;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+// The institute's letter-grade scale, bundled like the curricula.
+const BUNDLED_GRADE_SCALE: &str = include_str!("../curricula/grade-scale.toml");
+
+lazy_static! {
+    static ref DEFAULT_SCALE: GradeScale =
+        GradeScale::from_toml_str(BUNDLED_GRADE_SCALE).expect("bundled grade scale is invalid");
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Grade {
+    pub letter: String,
+    pub points: f32,
+}
+
+// Maps letter grades (AA, AB, ... FF) to grade points. Grades are kept in
+// the order they were defined, best first.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GradeScale {
+    #[serde(rename = "grade")]
+    grades: Vec<Grade>,
+}
+
+impl GradeScale {
+    pub fn from_toml_str(src: &str) -> Result<Self, CurriculumError> {
+        let scale: GradeScale =
+            toml::from_str(src).map_err(|e| CurriculumError::Parse(e.to_string()))?;
+        scale.validate()
+    }
+
+    pub fn from_json_str(src: &str) -> Result<Self, CurriculumError> {
+        let scale: GradeScale =
+            serde_json::from_str(src).map_err(|e| CurriculumError::Parse(e.to_string()))?;
+        scale.validate()
+    }
+
+    pub fn grades(&self) -> &[Grade] {
+        &self.grades
+    }
+
+    // Grade points for a letter, ignoring case and surrounding whitespace.
+    pub fn points(&self, letter: &str) -> Option<f32> {
+        let letter = letter.trim();
+        self.grades
+            .iter()
+            .find(|g| g.letter.eq_ignore_ascii_case(letter))
+            .map(|g| g.points)
+    }
+
+    // A failing grade is one worth no points (FF on the default scale).
+    pub fn is_fail(&self, letter: &str) -> bool {
+        self.points(letter) == Some(0.0)
+    }
+
+    fn validate(self) -> Result<Self, CurriculumError> {
+        if self.grades.is_empty() {
+            return Err(CurriculumError::Invalid("grade scale is empty".to_string()));
+        }
+        for (i, grade) in self.grades.iter().enumerate() {
+            if grade.letter.trim().is_empty() {
+                return Err(CurriculumError::Invalid(
+                    "grade letter is empty".to_string(),
+                ));
+            }
+            if !(0.0..=10.0).contains(&grade.points) {
+                return Err(CurriculumError::Invalid(format!(
+                    "grade {} has points {} outside 0-10",
+                    grade.letter, grade.points
+                )));
+            }
+            if self.grades[..i]
+                .iter()
+                .any(|g| g.letter.eq_ignore_ascii_case(&grade.letter))
+            {
+                return Err(CurriculumError::Invalid(format!(
+                    "grade {} is defined twice",
+                    grade.letter
+                )));
+            }
+            // Neighbouring grades (one better, one worse) are found by position
+            if let Some(better) = i.checked_sub(1).map(|j| &self.grades[j]) {
+                if grade.points > better.points {
+                    return Err(CurriculumError::Invalid(format!(
+                        "grade {} is worth more than {} above it; list grades best first",
+                        grade.letter, better.letter
+                    )));
+                }
+            }
+        }
+        Ok(self)
+    }
+}
+
+// The bundled grade scale, parsed once on first use.
+pub fn default_scale() -> &'static GradeScale {
+    &DEFAULT_SCALE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_scale_runs_best_first() {
+        let points: Vec<f32> = default_scale().grades().iter().map(|g| g.points).collect();
+        assert_eq!(points, [10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 0.0]);
+    }
+
+    #[test]
+    fn scale_out_of_order_is_rejected() {
+        let src = r#"
+            [[grade]]
+            letter = "P"
+            points = 5.0
+
+            [[grade]]
+            letter = "A"
+            points = 10.0
+        "#;
+        let err = GradeScale::from_toml_str(src).unwrap_err();
+        assert!(err.to_string().contains("best first"), "{}", err);
+    }
+}
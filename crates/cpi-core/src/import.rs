@@ -0,0 +1,618 @@
+use crate::grades::GradeScale;
+use crate::semdata::{Curriculum, SemesterId};
+use serde::Deserialize;
+use std::fmt;
+
+// Grades brought in from elsewhere instead of being picked one by one: a CSV
+// or JSON transcript (the files `export` writes are accepted back) or text
+// copied from the academic portal, one course code and letter grade per
+// line. `parse` reads them; `fill_grades` places them against a curriculum.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedGrade {
+    pub code: String,
+    pub grade: String,
+    pub semester: Option<u8>, // None when the source does not say
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImportError {
+    Empty,
+    Json(String),
+    Csv(String),
+    NothingFound,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Empty => write!(f, "nothing to import"),
+            ImportError::Json(msg) => write!(f, "invalid JSON: {}", msg),
+            ImportError::Csv(msg) => write!(f, "invalid CSV: {}", msg),
+            ImportError::NothingFound => write!(f, "no course codes with grades found"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+// One semester's inputs as the frontend holds them, updated in place by
+// `fill_grades`: a grade (empty when not entered) and an optional elective
+// code per course slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemesterGrades {
+    pub id: SemesterId,
+    pub grades: Vec<String>,
+    pub electives: Vec<Option<String>>,
+}
+
+// What `fill_grades` did with the imported entries. Codes are listed once each.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub matched: usize,
+    pub unmatched: Vec<String>, // Not a course (or open elective slot) of any semester given
+    pub ambiguous: Vec<String>, // Fits several semesters, or graded twice with different grades
+    pub invalid: Vec<String>,   // Grade not on the scale
+    pub other_semesters: usize, // Entries for semesters that were not given
+}
+
+// Reads grades from JSON (starting with `{` or `[`), CSV with a header row
+// naming `code` and `grade` columns, or else pasted text.
+pub fn parse(text: &str, scale: &GradeScale) -> Result<Vec<ImportedGrade>, ImportError> {
+    let text = text.trim_start_matches('\u{feff}').trim();
+    if text.is_empty() {
+        return Err(ImportError::Empty);
+    }
+    let found = if text.starts_with('{') || text.starts_with('[') {
+        parse_json(text)?
+    } else {
+        match parse_csv(text)? {
+            Some(found) => found,
+            None => parse_text(text, scale),
+        }
+    };
+    if found.is_empty() {
+        return Err(ImportError::NothingFound);
+    }
+    Ok(found)
+}
+
+// The `export` layout ({ "semesters": [{ "semester": 3, "courses": [...] }] })
+// or a plain list of { "code", "grade", "semester"? } objects.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonImport {
+    Export { semesters: Vec<JsonSemester> },
+    Courses(Vec<JsonCourse>),
+}
+
+#[derive(Deserialize)]
+struct JsonSemester {
+    semester: Option<u8>,
+    courses: Vec<JsonCourse>,
+}
+
+#[derive(Deserialize)]
+struct JsonCourse {
+    code: String,
+    grade: String,
+    #[serde(default)]
+    semester: Option<u8>,
+}
+
+fn parse_json(text: &str) -> Result<Vec<ImportedGrade>, ImportError> {
+    let import: JsonImport =
+        serde_json::from_str(text).map_err(|e| ImportError::Json(e.to_string()))?;
+    let imported = |c: JsonCourse, semester: Option<u8>| ImportedGrade {
+        code: c.code,
+        grade: c.grade,
+        semester: c.semester.or(semester),
+    };
+    Ok(match import {
+        JsonImport::Export { semesters } => semesters
+            .into_iter()
+            .flat_map(|sem| {
+                sem.courses
+                    .into_iter()
+                    .map(move |c| imported(c, sem.semester))
+            })
+            .collect(),
+        JsonImport::Courses(courses) => courses.into_iter().map(|c| imported(c, None)).collect(),
+    })
+}
+
+// None when the first line is not a header with code and grade columns.
+// Rows without a code or grade (e.g. an SPI-only row of an export) are skipped.
+fn parse_csv(text: &str) -> Result<Option<Vec<ImportedGrade>>, ImportError> {
+    let header = text.lines().next().unwrap_or_default();
+    let names: Vec<String> = header
+        .split(',')
+        .map(|h| h.trim().trim_matches('"').to_lowercase())
+        .collect();
+    let column = |candidates: &[&str]| names.iter().position(|n| candidates.contains(&n.as_str()));
+    let (code_col, grade_col) = match (
+        column(&["code", "course code", "course_code"]),
+        column(&["grade", "letter grade"]),
+    ) {
+        (Some(code), Some(grade)) => (code, grade),
+        _ => return Ok(None),
+    };
+    let semester_col = column(&["semester", "sem"]);
+    let mut found = Vec::new();
+    for (line, record) in csv_records(text)?.into_iter().enumerate().skip(1) {
+        let field = |col: usize| record.get(col).map_or("", |v| v.trim());
+        let (code, grade) = (field(code_col), field(grade_col));
+        if code.is_empty() || grade.is_empty() {
+            continue;
+        }
+        let semester = match semester_col.map(field).filter(|s| !s.is_empty()) {
+            Some(s) => Some(s.parse().map_err(|_| {
+                ImportError::Csv(format!("record {}: invalid semester '{}'", line + 1, s))
+            })?),
+            None => None,
+        };
+        found.push(ImportedGrade {
+            code: code.to_string(),
+            grade: grade.to_string(),
+            semester,
+        });
+    }
+    Ok(Some(found))
+}
+
+// Splits CSV into records of fields, undoing the quoting `export` applies
+// (RFC 4180). Blank lines are dropped.
+fn csv_records(text: &str) -> Result<Vec<Vec<String>>, ImportError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(ImportError::Csv("unterminated quoted field".to_string()));
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// Portal text: a line holding a course code (CS201, or CS 201) and, after
+// it, a grade on the scale counts; the last such grade wins, so names and
+// credit columns in between are fine. Lines like "Semester 3" or
+// "Semester - III" set the semester for the lines below.
+fn parse_text(text: &str, scale: &GradeScale) -> Vec<ImportedGrade> {
+    let mut found = Vec::new();
+    let mut semester = None;
+    for line in text.lines() {
+        let tokens: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || matches!(c, '|' | ',' | ';'))
+            .map(|t| t.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+            .filter(|t| !t.is_empty())
+            .collect();
+        match course_code(&tokens) {
+            Some((code, rest)) => {
+                if let Some(grade) = tokens[rest..]
+                    .iter()
+                    .rev()
+                    .find(|t| scale.points(t).is_some())
+                {
+                    found.push(ImportedGrade {
+                        code,
+                        grade: grade.to_string(),
+                        semester,
+                    });
+                }
+            }
+            None => {
+                if let Some(number) = semester_heading(line) {
+                    semester = Some(number);
+                }
+            }
+        }
+    }
+    found
+}
+
+// The first course code among `tokens` and the index of the token after it
+fn course_code(tokens: &[&str]) -> Option<(String, usize)> {
+    for (i, token) in tokens.iter().enumerate() {
+        if is_course_code(token) {
+            return Some((token.to_uppercase(), i + 1));
+        }
+        // Department and number written apart
+        if let Some(next) = tokens.get(i + 1) {
+            let joined = format!("{}{}", token, next);
+            if token.chars().all(|c| c.is_ascii_alphabetic()) && is_course_code(&joined) {
+                return Some((joined.to_uppercase(), i + 2));
+            }
+        }
+    }
+    None
+}
+
+// Two to four letters, then a digit, then letters or digits: CS201, MA101A, CS4XX
+fn is_course_code(token: &str) -> bool {
+    let letters = token
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .count();
+    (2..=4).contains(&letters)
+        && (5..=8).contains(&token.len())
+        && token[letters..].starts_with(|c: char| c.is_ascii_digit())
+        && token.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn semester_heading(line: &str) -> Option<u8> {
+    const ROMAN: [&str; 12] = [
+        "i", "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x", "xi", "xii",
+    ];
+    let words: Vec<String> = line
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let at = words.iter().position(|w| w == "semester" || w == "sem")?;
+    let number = words.get(at + 1)?;
+    number
+        .parse()
+        .ok()
+        .or_else(|| ROMAN.iter().position(|r| r == number).map(|i| i as u8 + 1))
+        .filter(|&n| n > 0)
+}
+
+// Where an imported grade goes within one semester
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Slot {
+    Course(usize),   // A course of that code, not yet graded by the import
+    Elective(usize), // An open placeholder slot the catalog course can fill
+    Repeated(usize), // Already graded by this import
+}
+
+// A semester being filled: its course codes with chosen electives in, and
+// which courses the import has graded so far
+struct Target<'a> {
+    sem: &'a mut SemesterGrades,
+    codes: Vec<String>,
+    filled: Vec<bool>,
+}
+
+impl Target<'_> {
+    fn slot(&self, curriculum: &Curriculum, code: &str) -> Option<Slot> {
+        let same = |i: &usize| self.codes[*i].eq_ignore_ascii_case(code);
+        let mut courses = (0..self.codes.len()).filter(same).peekable();
+        if courses.peek().is_some() {
+            let courses: Vec<usize> = courses.collect();
+            return Some(match courses.iter().find(|&&i| !self.filled[i]) {
+                Some(&i) => Slot::Course(i),
+                None => Slot::Repeated(courses[0]),
+            });
+        }
+        (0..self.codes.len())
+            .find(|&i| {
+                !self.filled[i]
+                    && matches!(self.sem.electives.get(i), None | Some(None))
+                    && curriculum
+                        .electives_for(self.sem.id, i)
+                        .iter()
+                        .any(|e| e.code.eq_ignore_ascii_case(code))
+            })
+            .map(Slot::Elective)
+    }
+}
+
+// Places imported grades into `semesters`, overwriting what was there.
+// An entry naming its semester goes to that semester; one that does not goes
+// to the only given semester that has the course. Catalog electives fill the
+// first open placeholder slot that accepts them. A semester offered in
+// several tracks whose track is not chosen gets the track its imported
+// courses fit best.
+pub fn fill_grades(
+    curriculum: &Curriculum,
+    semesters: &mut [SemesterGrades],
+    imported: &[ImportedGrade],
+    scale: &GradeScale,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    for sem in semesters.iter_mut() {
+        if sem.id.track.is_none() && !curriculum.tracks(sem.id.number).is_empty() {
+            infer_track(curriculum, sem, imported);
+        }
+    }
+    let mut targets: Vec<Target> = semesters
+        .iter_mut()
+        .filter_map(|sem| {
+            let base = curriculum.semester(sem.id)?;
+            let count = base.course_code.len();
+            sem.grades.resize(count, String::new());
+            sem.electives.resize(count, None);
+            let codes = (0..count)
+                .map(|i| {
+                    match sem.electives[i]
+                        .as_deref()
+                        .and_then(|code| curriculum.elective(code))
+                    {
+                        Some(elective) => elective.code.clone(),
+                        None => base.course_code[i].clone(),
+                    }
+                })
+                .collect();
+            Some(Target {
+                sem,
+                codes,
+                filled: vec![false; count],
+            })
+        })
+        .collect();
+    let note = |list: &mut Vec<String>, code: &str| {
+        if !list.iter().any(|c| c.eq_ignore_ascii_case(code)) {
+            list.push(code.to_uppercase());
+        }
+    };
+    for entry in imported {
+        let code = entry.code.trim();
+        let grade = match scale
+            .grades()
+            .iter()
+            .find(|g| g.letter.eq_ignore_ascii_case(entry.grade.trim()))
+        {
+            Some(g) => g.letter.clone(),
+            None => {
+                note(&mut report.invalid, code);
+                continue;
+            }
+        };
+        let candidates: Vec<(usize, Slot)> = targets
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| entry.semester.is_none() || entry.semester == Some(t.sem.id.number))
+            .filter_map(|(i, t)| t.slot(curriculum, code).map(|slot| (i, slot)))
+            .collect();
+        let (target, slot) = match candidates.as_slice() {
+            [only] => *only,
+            [] if entry
+                .semester
+                .is_some_and(|n| !targets.iter().any(|t| t.sem.id.number == n)) =>
+            {
+                report.other_semesters += 1;
+                continue;
+            }
+            [] => {
+                note(&mut report.unmatched, code);
+                continue;
+            }
+            _ => {
+                note(&mut report.ambiguous, code);
+                continue;
+            }
+        };
+        let target = &mut targets[target];
+        match slot {
+            Slot::Repeated(i) => {
+                // The same grade twice is harmless; two different ones leave the first
+                if target.sem.grades[i] != grade {
+                    note(&mut report.ambiguous, code);
+                }
+                continue;
+            }
+            Slot::Elective(i) => {
+                let elective = curriculum
+                    .elective(code)
+                    .map(|e| e.code.clone())
+                    .unwrap_or_else(|| code.to_uppercase());
+                target.codes[i] = elective.clone();
+                target.sem.electives[i] = Some(elective);
+                target.sem.grades[i] = grade;
+                target.filled[i] = true;
+            }
+            Slot::Course(i) => {
+                target.sem.grades[i] = grade;
+                target.filled[i] = true;
+            }
+        }
+        report.matched += 1;
+    }
+    report
+}
+
+// Picks the track of `sem` that the most courses imported for it belong to;
+// leaves it unchosen when none fit
+fn infer_track(curriculum: &Curriculum, sem: &mut SemesterGrades, imported: &[ImportedGrade]) {
+    let entries: Vec<&ImportedGrade> = imported
+        .iter()
+        .filter(|e| e.semester == Some(sem.id.number))
+        .collect();
+    let mut best = (0, None);
+    for track in curriculum.tracks(sem.id.number) {
+        let id = SemesterId::with_track(sem.id.number, track);
+        let count = curriculum.semester(id).map_or(0, |s| s.course_code.len());
+        let mut candidate = SemesterGrades {
+            id,
+            grades: vec![String::new(); count],
+            electives: vec![None; count],
+        };
+        let mut target = Target {
+            codes: curriculum
+                .semester(id)
+                .map_or(Vec::new(), |s| s.course_code.clone()),
+            filled: vec![false; count],
+            sem: &mut candidate,
+        };
+        let mut fits = 0;
+        for entry in &entries {
+            match target.slot(curriculum, entry.code.trim()) {
+                Some(Slot::Course(i)) | Some(Slot::Elective(i)) => {
+                    target.filled[i] = true;
+                    fits += 1;
+                }
+                _ => {}
+            }
+        }
+        if fits > best.0 {
+            best = (fits, Some(track));
+        }
+    }
+    if let Some(track) = best.1 {
+        sem.id.track = Some(track);
+        sem.grades.clear();
+        sem.electives.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{self, Export, ExportSemester};
+    use crate::grades;
+    use crate::semdata::{self, Track};
+
+    fn cse() -> &'static Curriculum {
+        semdata::get_curriculum("cse", 2021).unwrap()
+    }
+
+    fn empty(curriculum: &Curriculum, id: SemesterId) -> SemesterGrades {
+        let count = curriculum.semester(id).map_or(0, |s| s.course_code.len());
+        SemesterGrades {
+            id,
+            grades: vec![String::new(); count],
+            electives: vec![None; count],
+        }
+    }
+
+    #[test]
+    fn portal_text_with_semester_headings() {
+        let text = "Semester - I\n\
+                    MA101\tMathematics I\t8\tab\n\
+                    CS 101 | Computer Programming | 6 | AA\n\
+                    Course Code  Course Name  Credits  Grade\n\
+                    SEMESTER 2\n\
+                    MA102 Mathematics II 8 BC\n";
+        let found = parse(text, grades::default_scale()).unwrap();
+        let summary: Vec<(&str, &str, Option<u8>)> = found
+            .iter()
+            .map(|g| (g.code.as_str(), g.grade.as_str(), g.semester))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("MA101", "ab", Some(1)),
+                ("CS101", "AA", Some(1)),
+                ("MA102", "BC", Some(2))
+            ]
+        );
+        assert_eq!(
+            parse("no grades here", grades::default_scale()),
+            Err(ImportError::NothingFound)
+        );
+    }
+
+    #[test]
+    fn exports_read_back() {
+        let curriculum = cse();
+        let sem = curriculum.semester(SemesterId::new(2)).unwrap();
+        let entered = vec!["AB".to_string(); sem.course_code.len()];
+        let semester =
+            ExportSemester::from_grades(sem, &entered, grades::default_scale(), 9.0, None).unwrap();
+        let export = Export {
+            programme: "cse".to_string(),
+            batch: 2021,
+            semesters: vec![semester],
+        };
+        for format in [export::ExportFormat::Csv, export::ExportFormat::Json] {
+            let found = parse(&format.render(&export), grades::default_scale()).unwrap();
+            assert_eq!(found.len(), sem.course_code.len());
+            assert!(found
+                .iter()
+                .all(|g| g.grade == "AB" && g.semester == Some(2)));
+        }
+    }
+
+    #[test]
+    fn fills_courses_and_electives_and_reports_the_rest() {
+        let curriculum = cse();
+        let scale = grades::default_scale();
+        let mut semesters = vec![
+            empty(curriculum, SemesterId::new(1)),
+            empty(
+                curriculum,
+                SemesterId {
+                    number: 7,
+                    track: None,
+                },
+            ),
+        ];
+        let imported = [
+            ImportedGrade {
+                code: "ma101".to_string(),
+                grade: "aa".to_string(),
+                semester: None,
+            },
+            ImportedGrade {
+                code: "MA101".to_string(),
+                grade: "BB".to_string(),
+                semester: Some(1),
+            },
+            ImportedGrade {
+                code: "CS400".to_string(),
+                grade: "AB".to_string(),
+                semester: Some(7),
+            },
+            ImportedGrade {
+                code: "CS432".to_string(),
+                grade: "BB".to_string(),
+                semester: Some(7),
+            },
+            ImportedGrade {
+                code: "XX999".to_string(),
+                grade: "AA".to_string(),
+                semester: None,
+            },
+            ImportedGrade {
+                code: "CS101".to_string(),
+                grade: "ZZ".to_string(),
+                semester: None,
+            },
+            ImportedGrade {
+                code: "MA102".to_string(),
+                grade: "AA".to_string(),
+                semester: Some(2),
+            },
+        ];
+        let report = fill_grades(curriculum, &mut semesters, &imported, scale);
+        assert_eq!(report.matched, 3);
+        assert_eq!(report.ambiguous, ["MA101"]);
+        assert_eq!(report.unmatched, ["XX999"]);
+        assert_eq!(report.invalid, ["CS101"]);
+        assert_eq!(report.other_semesters, 1);
+        assert_eq!(semesters[0].grades[0], "AA");
+        // Project II is only in the project track; the elective takes the first CS4XX slot
+        let seventh = &semesters[1];
+        assert_eq!(seventh.id.track, Some(Track::Project));
+        assert_eq!(seventh.grades[3], "AB");
+        assert_eq!(
+            (seventh.electives[1].as_deref(), seventh.grades[1].as_str()),
+            (Some("CS432"), "BB")
+        );
+    }
+}
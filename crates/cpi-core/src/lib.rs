@@ -0,0 +1,11 @@
+// Calculation core shared by the frontends: curricula, grade scales, SPI/CPI
+// calculations and the form that drives them, CSV/JSON export, grade import, shareable-link encoding and
+// the saved-state format. Nothing here touches the browser, so it builds and tests natively.
+pub mod export;
+pub mod form;
+pub mod functions;
+pub mod grades;
+pub mod import;
+pub mod semdata;
+pub mod share;
+pub mod storage;
@@ -0,0 +1,765 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Curricula are data, not code: edit the files under curricula/ to change
+// courses or credits, and add a file here to ship a new programme or batch
+// scheme. They are embedded at compile time so the WASM bundle has no extra
+// files to fetch. Only curricula with a known source belong here; the files
+// under curricula/samples/ are illustrations.
+const BUNDLED_CURRICULA: &[&str] = &[include_str!("../curricula/cse.toml")];
+
+// Slack allowed when comparing credit sums, which are f32.
+const CREDIT_TOLERANCE: f32 = 1e-3;
+
+// How many years back the batch picker reaches for a scheme with no first
+// batch: enough for anyone still studying, or recently graduated, under it.
+const OPEN_START_YEARS: u16 = 10;
+
+lazy_static! {
+    static ref PROGRAMMES: Vec<Programme> =
+        load_programmes(BUNDLED_CURRICULA).expect("bundled curricula are invalid");
+}
+
+// The variants a semester can be taken in. Only the final semesters offer
+// more than one; everything else has no track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Track {
+    Electives,
+    Project,
+    Internship,
+}
+
+// Identifies one semester of a curriculum: its number plus, where the
+// semester is offered in several variants, the chosen track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SemesterId {
+    pub number: u8,
+    pub track: Option<Track>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Semester {
+    pub id: SemesterId,
+    pub course_code: Vec<String>,
+    pub course_name: Vec<String>,
+    pub course_credit: Vec<f32>,
+    pub total_credit: f32, // Sum of course_credit
+}
+
+// Admission years (inclusive) a curriculum applies to; `to: None` means the
+// scheme is still in force, `from: None` that it also covers every batch
+// before `to` (its first batch is not on record).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchRange {
+    #[serde(default)]
+    pub from: Option<u16>,
+    #[serde(default)]
+    pub to: Option<u16>,
+}
+
+// A catalog course that can fill an elective placeholder slot (HSXXX,
+// CS4XX, ...). `category` is the placeholder code it stands in for.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Elective {
+    pub code: String,
+    pub name: String,
+    pub credit: f32,
+    pub category: String,
+    pub offered: Vec<u8>, // Semester numbers the course runs in
+}
+
+#[derive(Debug, Clone)]
+pub struct Curriculum {
+    pub batches: BatchRange,
+    pub semesters: Vec<Semester>,
+    pub electives: Vec<Elective>,
+}
+
+// A degree programme (CSE, ECE, ...) and the curricula its batches follow.
+#[derive(Debug, Clone)]
+pub struct Programme {
+    pub id: String,
+    pub name: String,
+    pub curricula: Vec<Curriculum>,
+}
+
+#[derive(Debug)]
+pub enum CurriculumError {
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for CurriculumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurriculumError::Parse(msg) => write!(f, "could not parse curriculum: {}", msg),
+            CurriculumError::Invalid(msg) => write!(f, "invalid curriculum: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CurriculumError {}
+
+// --- On-disk format ---
+// A curriculum file names its `[programme]` and the `[batches]` it applies
+// to, then lists `[[semester]]` tables, each holding its `[[semester.course]]`
+// entries, and optionally an `[[elective]]` catalog. JSON files use the same
+// field names.
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CurriculumFile {
+    programme: ProgrammeEntry,
+    batches: BatchRange,
+    #[serde(rename = "semester")]
+    semesters: Vec<SemesterEntry>,
+    #[serde(default, rename = "elective")]
+    electives: Vec<Elective>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProgrammeEntry {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SemesterEntry {
+    number: u8,
+    #[serde(default)]
+    track: Option<Track>, // Only for semesters offered in several variants (7/8)
+    #[serde(rename = "course")]
+    courses: Vec<CourseEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CourseEntry {
+    code: String,
+    name: String,
+    credit: f32,
+}
+
+impl Track {
+    pub const ALL: [Track; 3] = [Track::Electives, Track::Project, Track::Internship];
+
+    // Stable lowercase name, as used in curriculum files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Track::Electives => "electives",
+            Track::Project => "project",
+            Track::Internship => "internship",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Track::Electives => "Electives",
+            Track::Project => "Project",
+            Track::Internship => "Internship",
+        }
+    }
+}
+
+impl FromStr for Track {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Track::ALL
+            .into_iter()
+            .find(|t| t.id().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown track '{}'", s))
+    }
+}
+
+impl fmt::Display for Track {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl SemesterId {
+    pub fn new(number: u8) -> Self {
+        SemesterId {
+            number,
+            track: None,
+        }
+    }
+
+    pub fn with_track(number: u8, track: Track) -> Self {
+        SemesterId {
+            number,
+            track: Some(track),
+        }
+    }
+}
+
+impl fmt::Display for SemesterId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.track {
+            Some(track) => write!(f, "{} ({})", self.number, track),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
+
+impl BatchRange {
+    pub fn contains(&self, batch: u16) -> bool {
+        batch >= self.from.unwrap_or(0) && batch <= self.to.unwrap_or(u16::MAX)
+    }
+
+    fn overlaps(&self, other: &BatchRange) -> bool {
+        let self_to = self.to.unwrap_or(u16::MAX);
+        let other_to = other.to.unwrap_or(u16::MAX);
+        self.from.unwrap_or(0) <= other_to && other.from.unwrap_or(0) <= self_to
+    }
+}
+
+impl fmt::Display for BatchRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.from, self.to) {
+            (Some(from), Some(to)) if to == from => write!(f, "{}", from),
+            (Some(from), Some(to)) => write!(f, "{}-{}", from, to),
+            (Some(from), None) => write!(f, "{} onwards", from),
+            (None, Some(to)) => write!(f, "up to {}", to),
+            (None, None) => write!(f, "every batch"),
+        }
+    }
+}
+
+impl Curriculum {
+    pub fn semester(&self, id: SemesterId) -> Option<&Semester> {
+        self.semesters.iter().find(|sem| sem.id == id)
+    }
+
+    // Distinct semester numbers, in order.
+    pub fn semester_numbers(&self) -> Vec<u8> {
+        let mut numbers: Vec<u8> = self.semesters.iter().map(|s| s.id.number).collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        numbers
+    }
+
+    // Tracks offered for semester `number`; empty if it has a single variant.
+    pub fn tracks(&self, number: u8) -> Vec<Track> {
+        let mut tracks: Vec<Track> = self
+            .semesters
+            .iter()
+            .filter(|s| s.id.number == number)
+            .filter_map(|s| s.id.track)
+            .collect();
+        tracks.sort_unstable();
+        tracks
+    }
+
+    // Inconsistencies that would make SPI/CPI figures silently wrong: gaps in
+    // the semester numbering, and semester totals that do not match their
+    // courses. Loading rejects a curriculum with any; this is public so
+    // curricula built or edited in code can be checked the same way. Credits
+    // up to a semester depend on the tracks and electives taken, so they are
+    // summed per path by `functions::path_credits` rather than stored.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let numbers = self.semester_numbers();
+        for (expected, &number) in (1..).zip(&numbers) {
+            if number != expected {
+                issues.push(format!("semester {} is missing", expected));
+                break;
+            }
+        }
+        for sem in &self.semesters {
+            let sum: f32 = sem.course_credit.iter().sum();
+            if (sem.total_credit - sum).abs() > CREDIT_TOLERANCE {
+                issues.push(format!(
+                    "semester {} totals {} credits but its courses add up to {}",
+                    sem.id, sem.total_credit, sum
+                ));
+            }
+        }
+        issues
+    }
+
+    pub fn elective(&self, code: &str) -> Option<&Elective> {
+        self.electives
+            .iter()
+            .find(|e| e.code.eq_ignore_ascii_case(code.trim()))
+    }
+
+    // Catalog courses that can fill course `index` of semester `id`; empty
+    // unless that course is a placeholder.
+    pub fn electives_for(&self, id: SemesterId, index: usize) -> Vec<&Elective> {
+        let placeholder = match self.semester(id).and_then(|sem| sem.course_code.get(index)) {
+            Some(code) => code,
+            None => return Vec::new(),
+        };
+        self.electives
+            .iter()
+            .filter(|e| e.category == *placeholder && e.offered.contains(&id.number))
+            .collect()
+    }
+}
+
+impl Programme {
+    pub fn from_toml_str(src: &str) -> Result<Self, CurriculumError> {
+        let file: CurriculumFile =
+            toml::from_str(src).map_err(|e| CurriculumError::Parse(e.to_string()))?;
+        Self::from_file(file)
+    }
+
+    pub fn from_json_str(src: &str) -> Result<Self, CurriculumError> {
+        let file: CurriculumFile =
+            serde_json::from_str(src).map_err(|e| CurriculumError::Parse(e.to_string()))?;
+        Self::from_file(file)
+    }
+
+    // The curriculum that applies to students admitted in `batch`.
+    pub fn curriculum(&self, batch: u16) -> Option<&Curriculum> {
+        self.curricula.iter().find(|c| c.batches.contains(batch))
+    }
+
+    // Every admission year up to `latest` that some curriculum covers, oldest
+    // first. A scheme with no first batch is listed from OPEN_START_YEARS
+    // before its last one.
+    pub fn batches(&self, latest: u16) -> Vec<u16> {
+        let earliest = self
+            .curricula
+            .iter()
+            .map(|c| match c.batches.from {
+                Some(from) => from,
+                None => c
+                    .batches
+                    .to
+                    .unwrap_or(latest)
+                    .min(latest)
+                    .saturating_sub(OPEN_START_YEARS),
+            })
+            .min();
+        match earliest {
+            Some(earliest) => (earliest..=latest)
+                .filter(|&year| self.curriculum(year).is_some())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // The most recent admission year up to `latest` with a curriculum, for
+    // callers that take the batch as optional.
+    pub fn newest_batch(&self, latest: u16) -> Option<u16> {
+        self.batches(latest).last().copied()
+    }
+
+    // Merges the curricula of another file for the same programme.
+    fn merge(&mut self, other: Programme) -> Result<(), CurriculumError> {
+        if other.name != self.name {
+            return Err(CurriculumError::Invalid(format!(
+                "programme {} is named both '{}' and '{}'",
+                self.id, self.name, other.name
+            )));
+        }
+        for curriculum in other.curricula {
+            if let Some(existing) = self
+                .curricula
+                .iter()
+                .find(|c| c.batches.overlaps(&curriculum.batches))
+            {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: batches {} and {} overlap",
+                    self.id, existing.batches, curriculum.batches
+                )));
+            }
+            self.curricula.push(curriculum);
+        }
+        Ok(())
+    }
+
+    fn from_file(file: CurriculumFile) -> Result<Self, CurriculumError> {
+        let ProgrammeEntry { id, name } = file.programme;
+        if id.trim().is_empty() {
+            return Err(CurriculumError::Invalid(
+                "programme id is empty".to_string(),
+            ));
+        }
+        if let (Some(from), Some(to)) = (file.batches.from, file.batches.to) {
+            if to < from {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: batch range {}-{} is reversed",
+                    id, from, to
+                )));
+            }
+        }
+        if file.semesters.is_empty() {
+            return Err(CurriculumError::Invalid(format!(
+                "programme {} has no semesters",
+                id
+            )));
+        }
+
+        let mut semesters: Vec<Semester> = Vec::with_capacity(file.semesters.len());
+        for entry in file.semesters {
+            if entry.number == 0 {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: semester numbers start at 1",
+                    id
+                )));
+            }
+            let sem_id = SemesterId {
+                number: entry.number,
+                track: entry.track,
+            };
+
+            if semesters.iter().any(|s| s.id == sem_id) {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: semester {} is defined twice",
+                    id, sem_id
+                )));
+            }
+            if semesters.iter().any(|s| {
+                s.id.number == sem_id.number && s.id.track.is_some() != sem_id.track.is_some()
+            }) {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: semester {} mixes tracked and untracked entries",
+                    id, sem_id.number
+                )));
+            }
+            if entry.courses.is_empty() {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: semester {} has no courses",
+                    id, sem_id
+                )));
+            }
+            if let Some(course) = entry
+                .courses
+                .iter()
+                .find(|c| !c.credit.is_finite() || c.credit <= 0.0)
+            {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: course {} in semester {} has invalid credit {}",
+                    id, course.code, sem_id, course.credit
+                )));
+            }
+
+            let course_credit: Vec<f32> = entry.courses.iter().map(|c| c.credit).collect();
+            semesters.push(Semester {
+                id: sem_id,
+                course_code: entry.courses.iter().map(|c| c.code.clone()).collect(),
+                course_name: entry.courses.iter().map(|c| c.name.clone()).collect(),
+                total_credit: course_credit.iter().sum(),
+                course_credit,
+            });
+        }
+
+        for (i, elective) in file.electives.iter().enumerate() {
+            if elective.code.trim().is_empty() {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: elective {} has no code",
+                    id,
+                    i + 1
+                )));
+            }
+            if file.electives[..i]
+                .iter()
+                .any(|e| e.code.eq_ignore_ascii_case(&elective.code))
+            {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: elective {} is listed twice",
+                    id, elective.code
+                )));
+            }
+            if !elective.credit.is_finite() || elective.credit <= 0.0 {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: elective {} has invalid credit {}",
+                    id, elective.code, elective.credit
+                )));
+            }
+            if elective.offered.is_empty() {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: elective {} is not offered in any semester",
+                    id, elective.code
+                )));
+            }
+            // Every offered semester must have a slot the elective can fill
+            if let Some(number) = elective.offered.iter().find(|&&n| {
+                !semesters
+                    .iter()
+                    .any(|s| s.id.number == n && s.course_code.contains(&elective.category))
+            }) {
+                return Err(CurriculumError::Invalid(format!(
+                    "{}: elective {} is offered in semester {}, which has no {} slot",
+                    id, elective.code, number, elective.category
+                )));
+            }
+        }
+
+        let curriculum = Curriculum {
+            batches: file.batches,
+            semesters,
+            electives: file.electives,
+        };
+        let issues = curriculum.issues();
+        if !issues.is_empty() {
+            return Err(CurriculumError::Invalid(format!(
+                "{}: {}",
+                id,
+                issues.join("; ")
+            )));
+        }
+
+        Ok(Programme {
+            id,
+            name,
+            curricula: vec![curriculum],
+        })
+    }
+}
+
+// Parses a set of curriculum files (TOML) into programmes. Files sharing a
+// programme id are merged, provided their batch ranges do not overlap.
+pub fn load_programmes(sources: &[&str]) -> Result<Vec<Programme>, CurriculumError> {
+    let mut programmes: Vec<Programme> = Vec::with_capacity(sources.len());
+    for src in sources {
+        let programme = Programme::from_toml_str(src)?;
+        match programmes.iter_mut().find(|p| p.id == programme.id) {
+            Some(existing) => existing.merge(programme)?,
+            None => programmes.push(programme),
+        }
+    }
+    Ok(programmes)
+}
+
+// All bundled programmes, parsed once on first use.
+pub fn programmes() -> &'static [Programme] {
+    &PROGRAMMES
+}
+
+pub fn get_programme(programme_id: &str) -> Option<&'static Programme> {
+    programmes().iter().find(|p| p.id == programme_id)
+}
+
+pub fn get_curriculum(programme_id: &str, batch: u16) -> Option<&'static Curriculum> {
+    get_programme(programme_id)?.curriculum(batch)
+}
+
+pub fn get_semesters(programme_id: &str, batch: u16, id: SemesterId) -> Option<Semester> {
+    get_curriculum(programme_id, batch)?.semester(id).cloned()
+}
+
+// The current calendar year (UTC), the latest batch the native frontends
+// offer. The system clock is not available to WebAssembly, so the web app asks
+// the browser instead.
+pub fn current_year() -> u16 {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400) as i64;
+    // Civil-from-days (Howard Hinnant), keeping only the year
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let march_based_month = (5 * doy + 2) / 153;
+    let year = yoe + era * 400 + i64::from(march_based_month >= 10);
+    year as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_SEMESTERS: &str = r#"
+        [programme]
+        id = "test"
+        name = "Test"
+
+        [batches]
+        from = 2020
+
+        [[semester]]
+        number = 1
+
+        [[semester.course]]
+        code = "T101"
+        name = "First"
+        credit = 6.0
+
+        [[semester.course]]
+        code = "T102"
+        name = "Second"
+        credit = 8.0
+
+        [[semester]]
+        number = 2
+
+        [[semester.course]]
+        code = "T201"
+        name = "Third"
+        credit = 10.0
+    "#;
+
+    #[test]
+    fn sample_programme_loads_beside_bundled_ones() {
+        let mut sources = BUNDLED_CURRICULA.to_vec();
+        sources.push(include_str!("../curricula/samples/ece-sample.toml"));
+        let loaded = load_programmes(&sources).unwrap();
+        assert!(loaded.iter().any(|p| p.id == "ece"));
+        // Sample data stays out of the calculators
+        assert!(get_programme("ece").is_none());
+    }
+
+    #[test]
+    fn catalogs_do_not_repeat_core_courses() {
+        let mut sources = BUNDLED_CURRICULA.to_vec();
+        sources.push(include_str!("../curricula/samples/ece-sample.toml"));
+        for programme in load_programmes(&sources).unwrap() {
+            for curriculum in &programme.curricula {
+                for semester in &curriculum.semesters {
+                    for elective in &curriculum.electives {
+                        assert!(
+                            !semester.course_code.contains(&elective.code)
+                                && !semester.course_name.contains(&elective.name),
+                            "{}: elective {} repeats a core course of semester {}",
+                            programme.id,
+                            elective.code,
+                            semester.id
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Two schemes of one programme: the original one, whose first batch is not
+    // known, retired after 2019, and a revision from 2020 with a lighter first semester
+    const OLD_SCHEME: &str = r#"
+        [programme]
+        id = "test"
+        name = "Test"
+
+        [batches]
+        to = 2019
+
+        [[semester]]
+        number = 1
+
+        [[semester.course]]
+        code = "T101"
+        name = "First"
+        credit = 8.0
+    "#;
+
+    #[test]
+    fn batches_pick_their_scheme() {
+        let new_scheme = TWO_SEMESTERS.replace("credit = 6.0", "credit = 4.0");
+        let programme = load_programmes(&[OLD_SCHEME, &new_scheme])
+            .unwrap()
+            .remove(0);
+        let first_credits = |batch: u16| {
+            programme
+                .curriculum(batch)
+                .and_then(|c| c.semester(SemesterId::new(1)))
+                .map(|s| s.total_credit)
+        };
+        assert_eq!(first_credits(1995), Some(8.0));
+        assert_eq!(first_credits(2019), Some(8.0));
+        assert_eq!(first_credits(2020), Some(12.0));
+        assert_eq!(first_credits(2030), Some(12.0));
+        assert_eq!(programme.batches(2022), (2009..=2022).collect::<Vec<u16>>());
+        assert_eq!(programme.newest_batch(2022), Some(2022));
+        assert_eq!(programme.curricula[0].batches.to_string(), "up to 2019");
+        assert_eq!(programme.curricula[1].batches.to_string(), "2020 onwards");
+    }
+
+    #[test]
+    fn overlapping_schemes_are_rejected() {
+        let open_ended = OLD_SCHEME.replace("to = 2019", "");
+        assert!(matches!(
+            load_programmes(&[&open_ended, TWO_SEMESTERS]),
+            Err(CurriculumError::Invalid(_))
+        ));
+        let reversed = TWO_SEMESTERS.replace("from = 2020", "from = 2020\nto = 2018");
+        assert!(matches!(
+            Programme::from_toml_str(&reversed),
+            Err(CurriculumError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn bundled_scheme_covers_earlier_batches() {
+        let cse = get_programme("cse").unwrap();
+        assert!(cse.curriculum(2012).is_some());
+        assert_eq!(cse.newest_batch(2024), Some(2024));
+    }
+
+    #[test]
+    fn bundled_curricula_are_consistent() {
+        for programme in programmes() {
+            for curriculum in &programme.curricula {
+                assert_eq!(
+                    curriculum.issues(),
+                    Vec::<String>::new(),
+                    "{} {}",
+                    programme.id,
+                    curriculum.batches
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn totals_are_derived_from_courses() {
+        let programme = Programme::from_toml_str(TWO_SEMESTERS).unwrap();
+        let curriculum = programme.curriculum(2020).unwrap();
+        let first = curriculum.semester(SemesterId::new(1)).unwrap();
+        let second = curriculum.semester(SemesterId::new(2)).unwrap();
+        assert_eq!(first.total_credit, 14.0);
+        assert_eq!(second.total_credit, 10.0);
+    }
+
+    #[test]
+    fn hand_written_totals_are_rejected() {
+        let src = TWO_SEMESTERS.replacen("number = 1", "number = 1\ntotal_credit = 14.0", 1);
+        assert!(matches!(
+            Programme::from_toml_str(&src),
+            Err(CurriculumError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn missing_semester_is_reported() {
+        let src = TWO_SEMESTERS.replace("number = 2", "number = 3");
+        match Programme::from_toml_str(&src) {
+            Err(CurriculumError::Invalid(msg)) => {
+                assert!(msg.contains("semester 2 is missing"), "{}", msg)
+            }
+            other => panic!(
+                "expected an invalid curriculum, got {:?}",
+                other.map(|p| p.id)
+            ),
+        }
+    }
+
+    #[test]
+    fn drifted_totals_are_reported() {
+        let mut curriculum = Programme::from_toml_str(TWO_SEMESTERS)
+            .unwrap()
+            .curricula
+            .remove(0);
+        curriculum.semesters[0].total_credit = 16.0;
+        let issues = curriculum.issues();
+        assert!(
+            issues
+                .iter()
+                .any(|i| i == "semester 1 totals 16 credits but its courses add up to 14"),
+            "{:?}",
+            issues
+        );
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+    }
+}
@@ -0,0 +1,453 @@
+use crate::functions::{CourseRef, Repeat, RepeatPolicy};
+use crate::semdata::Track;
+use crate::storage::{SavedTarget, SavedTranscriptRow};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Format version written into every link. Bump it when the meaning of a key
+// changes; links carrying another version are rejected instead of misread.
+const SHARE_VERSION: &str = "1";
+
+// A calculation as carried in a shareable link. Inputs are kept as entered,
+// like the saved state, so the link reproduces exactly what was on screen.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SharedState {
+    pub calc_type: Option<i32>,
+    pub programme: Option<String>,
+    pub batch: Option<u16>,
+    pub sem_no: Option<u8>,
+    pub track: Option<Track>,
+    pub cpi_option: Option<i32>,
+    pub grades: Vec<String>,
+    pub electives: Vec<Option<String>>, // Per course slot, like the grades
+    pub prev_cpi: Option<String>,
+    pub current_spi: Option<String>,
+    pub path_tracks: BTreeMap<u8, Track>, // Track taken in earlier semesters that had several
+    pub path_electives: BTreeMap<u8, Vec<Option<String>>>, // Electives taken in earlier semesters
+    pub transcript: Vec<SavedTranscriptRow>, // Full transcript mode, one per semester
+    pub repeat_policy: RepeatPolicy,
+    pub target: SavedTarget, // Target CPI planner
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ShareError {
+    UnsupportedVersion(String),
+    Malformed(String),
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareError::UnsupportedVersion(v) => write!(f, "unsupported link version '{}'", v),
+            ShareError::Malformed(msg) => write!(f, "malformed link: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+// Encodes a state as `key=value` pairs joined by `&`, for use as a URL
+// fragment, e.g. `v=1&c=0&p=cse&b=2021&s=3&g=AA,AB,BB`. Empty fields are left out.
+// Each transcript row is an `r` pair of `;`-separated track, grades, electives
+// and repeats, e.g. `r=project;AA,AB;,HS421;3.2:BB` (course 2 of semester 3,
+// counting from 0, retaken for a BB).
+pub fn encode(state: &SharedState) -> String {
+    let mut pairs: Vec<(&str, String)> = vec![("v", SHARE_VERSION.to_string())];
+    if let Some(c) = state.calc_type {
+        pairs.push(("c", c.to_string()));
+    }
+    if let Some(p) = &state.programme {
+        pairs.push(("p", escape(p)));
+    }
+    if let Some(b) = state.batch {
+        pairs.push(("b", b.to_string()));
+    }
+    if let Some(s) = state.sem_no {
+        pairs.push(("s", s.to_string()));
+    }
+    if let Some(t) = state.track {
+        pairs.push(("t", t.id().to_string()));
+    }
+    if let Some(o) = state.cpi_option {
+        pairs.push(("o", o.to_string()));
+    }
+    if !state.grades.is_empty() {
+        pairs.push(("g", join(&state.grades)));
+    }
+    if state.electives.iter().any(Option::is_some) {
+        pairs.push(("e", join_codes(&state.electives)));
+    }
+    if let Some(pc) = state.prev_cpi.as_deref().filter(|v| !v.is_empty()) {
+        pairs.push(("pc", escape(pc)));
+    }
+    if let Some(spi) = state.current_spi.as_deref().filter(|v| !v.is_empty()) {
+        pairs.push(("spi", escape(spi)));
+    }
+    if !state.path_tracks.is_empty() {
+        let tracks: Vec<String> = state
+            .path_tracks
+            .iter()
+            .map(|(n, t)| format!("{}:{}", n, t.id()))
+            .collect();
+        pairs.push(("pt", tracks.join(",")));
+    }
+    let path_electives: Vec<String> = state
+        .path_electives
+        .iter()
+        .filter(|(_, codes)| codes.iter().any(Option::is_some))
+        .map(|(n, codes)| format!("{}:{}", n, join_codes(codes)))
+        .collect();
+    if !path_electives.is_empty() {
+        pairs.push(("pe", path_electives.join(";")));
+    }
+    for row in &state.transcript {
+        let repeats: Vec<String> = row
+            .repeats
+            .iter()
+            .map(|r| {
+                format!(
+                    "{}.{}:{}",
+                    r.course.semester,
+                    r.course.course,
+                    escape(&r.grade)
+                )
+            })
+            .collect();
+        let track = row.track.map(|t| t.id()).unwrap_or_default();
+        let row = [
+            track.to_string(),
+            join(&row.grades),
+            join_codes(&row.electives),
+            repeats.join(","),
+        ];
+        pairs.push(("r", row.join(";")));
+    }
+    if state.repeat_policy != RepeatPolicy::default() {
+        pairs.push(("rp", state.repeat_policy.id().to_string()));
+    }
+    let target = &state.target;
+    if let Some(from) = target.from_sem {
+        pairs.push(("tf", semester_ref(from, target.from_track)));
+    }
+    if !target.current_cpi.is_empty() {
+        pairs.push(("tc", escape(&target.current_cpi)));
+    }
+    if let Some(to) = target.to_sem {
+        pairs.push(("tt", semester_ref(to, target.to_track)));
+    }
+    if !target.target_cpi.is_empty() {
+        pairs.push(("tg", escape(&target.target_cpi)));
+    }
+
+    pairs
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// Decodes a fragment produced by `encode`. A leading `#` is ignored, as are
+// keys this version does not know.
+pub fn decode(fragment: &str) -> Result<SharedState, ShareError> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let mut state = SharedState::default();
+    let mut version: Option<String> = None;
+
+    for pair in fragment.split('&').filter(|p| !p.is_empty()) {
+        let (key, raw) = pair
+            .split_once('=')
+            .ok_or_else(|| ShareError::Malformed(format!("'{}' has no value", pair)))?;
+        if key == "g" {
+            state.grades = raw.split(',').map(unescape).collect::<Result<_, _>>()?;
+            continue;
+        }
+        if key == "e" {
+            state.electives = codes(raw)?;
+            continue;
+        }
+        if key == "pe" {
+            for entry in raw.split(';') {
+                let (number, list) = split_pair(key, entry, ':')?;
+                state
+                    .path_electives
+                    .insert(parse(key, number)?, codes(list)?);
+            }
+            continue;
+        }
+        if key == "r" {
+            state.transcript.push(transcript_row(raw)?);
+            continue;
+        }
+        let value = unescape(raw)?;
+        match key {
+            "v" => version = Some(value),
+            "c" => state.calc_type = Some(parse(key, &value)?),
+            "p" => state.programme = Some(value),
+            "b" => state.batch = Some(parse(key, &value)?),
+            "s" => state.sem_no = Some(parse(key, &value)?),
+            "t" => state.track = Some(value.parse().map_err(ShareError::Malformed)?),
+            "o" => state.cpi_option = Some(parse(key, &value)?),
+            "pc" => state.prev_cpi = Some(value),
+            "spi" => state.current_spi = Some(value),
+            "pt" => {
+                for entry in value.split(',') {
+                    let (number, track) = split_pair(key, entry, ':')?;
+                    state.path_tracks.insert(
+                        parse(key, number)?,
+                        track.parse().map_err(ShareError::Malformed)?,
+                    );
+                }
+            }
+            "rp" => state.repeat_policy = value.parse().map_err(ShareError::Malformed)?,
+            "tf" => (state.target.from_sem, state.target.from_track) = semester(key, &value)?,
+            "tc" => state.target.current_cpi = value,
+            "tt" => (state.target.to_sem, state.target.to_track) = semester(key, &value)?,
+            "tg" => state.target.target_cpi = value,
+            _ => {}
+        }
+    }
+
+    match version.as_deref() {
+        Some(SHARE_VERSION) => Ok(state),
+        Some(other) => Err(ShareError::UnsupportedVersion(other.to_string())),
+        None => Err(ShareError::Malformed("missing version".to_string())),
+    }
+}
+
+fn escape(value: &str) -> String {
+    urlencoding::encode(value).into_owned()
+}
+
+fn join(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| escape(v))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Elective codes per course slot, empty where none was chosen
+fn join_codes(codes: &[Option<String>]) -> String {
+    codes
+        .iter()
+        .map(|c| escape(c.as_deref().unwrap_or_default()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn codes(raw: &str) -> Result<Vec<Option<String>>, ShareError> {
+    raw.split(',')
+        .map(|e| unescape(e).map(|code| Some(code).filter(|c| !c.is_empty())))
+        .collect()
+}
+
+// A semester number, with the track after a ':' when there is one
+fn semester_ref(number: u8, track: Option<Track>) -> String {
+    match track {
+        Some(track) => format!("{}:{}", number, track.id()),
+        None => number.to_string(),
+    }
+}
+
+fn semester(key: &str, value: &str) -> Result<(Option<u8>, Option<Track>), ShareError> {
+    let (number, track) = match value.split_once(':') {
+        Some((number, track)) => (number, Some(track.parse().map_err(ShareError::Malformed)?)),
+        None => (value, None),
+    };
+    Ok((Some(parse(key, number)?), track))
+}
+
+fn transcript_row(raw: &str) -> Result<SavedTranscriptRow, ShareError> {
+    let sections: Vec<&str> = raw.split(';').collect();
+    let [track, grades, electives, repeats] = sections[..] else {
+        return Err(ShareError::Malformed(format!(
+            "bad value '{}' for 'r'",
+            raw
+        )));
+    };
+    // A row whose track is still to be picked has no courses yet
+    let list = |raw: &str| -> Result<Vec<String>, ShareError> {
+        if raw.is_empty() {
+            return Ok(Vec::new());
+        }
+        raw.split(',').map(unescape).collect()
+    };
+    let repeats = list(repeats)?
+        .iter()
+        .map(|entry| {
+            let (course, grade) = split_pair("r", entry, ':')?;
+            let (semester, course) = split_pair("r", course, '.')?;
+            Ok(Repeat {
+                course: CourseRef {
+                    semester: parse("r", semester)?,
+                    course: parse("r", course)?,
+                },
+                grade: grade.to_string(),
+            })
+        })
+        .collect::<Result<_, ShareError>>()?;
+    Ok(SavedTranscriptRow {
+        track: match track {
+            "" => None,
+            track => Some(track.parse().map_err(ShareError::Malformed)?),
+        },
+        grades: list(grades)?,
+        electives: if electives.is_empty() {
+            Vec::new()
+        } else {
+            codes(electives)?
+        },
+        repeats,
+    })
+}
+
+fn split_pair<'a>(
+    key: &str,
+    entry: &'a str,
+    separator: char,
+) -> Result<(&'a str, &'a str), ShareError> {
+    entry
+        .split_once(separator)
+        .ok_or_else(|| ShareError::Malformed(format!("bad value '{}' for '{}'", entry, key)))
+}
+
+fn unescape(raw: &str) -> Result<String, ShareError> {
+    urlencoding::decode(raw)
+        .map(|v| v.into_owned())
+        .map_err(|_| ShareError::Malformed(format!("bad escape in '{}'", raw)))
+}
+
+fn parse<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, ShareError> {
+    value
+        .parse()
+        .map_err(|_| ShareError::Malformed(format!("bad value '{}' for '{}'", value, key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoding_an_encoded_state_gives_it_back() {
+        let state = SharedState {
+            calc_type: Some(1),
+            programme: Some("cse".to_string()),
+            batch: Some(2021),
+            sem_no: Some(8),
+            track: Some(Track::Project),
+            cpi_option: Some(3),
+            grades: vec!["AA".to_string(), String::new(), "A,B&C".to_string()],
+            electives: vec![None, Some("HS421".to_string()), None],
+            prev_cpi: Some("8.25".to_string()),
+            current_spi: Some("9".to_string()),
+            path_tracks: BTreeMap::from([(7, Track::Electives)]),
+            path_electives: BTreeMap::from([(7, vec![None, None, Some("CS441".to_string())])]),
+            ..Default::default()
+        };
+        let fragment = encode(&state);
+        assert!(fragment.starts_with("v=1&"));
+        assert_eq!(decode(&fragment), Ok(state.clone()));
+        assert_eq!(decode(&format!("#{}", fragment)), Ok(state));
+
+        assert_eq!(
+            decode("v=1&p=cse&x=ignored").unwrap().programme.as_deref(),
+            Some("cse")
+        );
+    }
+
+    #[test]
+    fn transcripts_survive_the_round_trip() {
+        let state = SharedState {
+            calc_type: Some(2),
+            programme: Some("cse".to_string()),
+            batch: Some(2021),
+            transcript: vec![
+                SavedTranscriptRow {
+                    track: None,
+                    grades: vec!["AA".to_string(), "FF".to_string(), String::new()],
+                    electives: vec![None, None, Some("HS201".to_string())],
+                    repeats: Vec::new(),
+                },
+                SavedTranscriptRow {
+                    track: None,
+                    grades: vec!["AB".to_string(), "BB".to_string()],
+                    electives: Vec::new(),
+                    repeats: vec![Repeat {
+                        course: CourseRef {
+                            semester: 1,
+                            course: 1,
+                        },
+                        grade: "BC".to_string(),
+                    }],
+                },
+                // Semester 7, before its track is picked
+                SavedTranscriptRow::default(),
+                SavedTranscriptRow {
+                    track: Some(Track::Project),
+                    grades: vec!["AA".to_string()],
+                    ..Default::default()
+                },
+            ],
+            repeat_policy: RepeatPolicy::Average,
+            ..Default::default()
+        };
+        let fragment = encode(&state);
+        assert!(fragment.contains("&r=;AB,BB;;1.1:BC&r=;;;&r=project;AA;;"));
+        assert_eq!(decode(&fragment), Ok(state));
+    }
+
+    #[test]
+    fn planner_and_what_if_inputs_survive_the_round_trip() {
+        let target = SharedState {
+            calc_type: Some(3),
+            programme: Some("cse".to_string()),
+            batch: Some(2021),
+            path_tracks: BTreeMap::from([(7, Track::Electives)]),
+            target: SavedTarget {
+                from_sem: Some(7),
+                from_track: Some(Track::Project),
+                current_cpi: "8.2".to_string(),
+                to_sem: Some(8),
+                to_track: None,
+                target_cpi: "8.5".to_string(),
+            },
+            ..Default::default()
+        };
+        assert_eq!(decode(&encode(&target)), Ok(target));
+
+        let what_if = SharedState {
+            calc_type: Some(4),
+            programme: Some("cse".to_string()),
+            batch: Some(2021),
+            sem_no: Some(8),
+            track: Some(Track::Project),
+            grades: vec!["AA".to_string()],
+            prev_cpi: Some("8.25".to_string()),
+            path_tracks: BTreeMap::from([(7, Track::Electives)]),
+            path_electives: BTreeMap::from([(7, vec![None, Some("CS441".to_string())])]),
+            ..Default::default()
+        };
+        assert_eq!(decode(&encode(&what_if)), Ok(what_if));
+    }
+
+    #[test]
+    fn malformed_links_are_rejected() {
+        let malformed = |fragment: &str| matches!(decode(fragment), Err(ShareError::Malformed(_)));
+        assert!(malformed(""));
+        assert!(malformed("p=cse"));
+        assert!(malformed("v=1&p"));
+        assert!(malformed("v=1&b=next"));
+        assert!(malformed("v=1&t=sabbatical"));
+        assert!(malformed("v=1&pt=7"));
+        assert!(malformed("v=1&pe=7"));
+        assert!(malformed("v=1&r=AA,AB"));
+        assert!(malformed("v=1&r=;AA;;1:AB"));
+        assert!(malformed("v=1&rp=sometimes"));
+        assert!(malformed("v=1&tf=7:gap"));
+        assert!(malformed("v=1&g=%E9"));
+        assert_eq!(
+            decode("v=2&p=cse"),
+            Err(ShareError::UnsupportedVersion("2".to_string()))
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::functions::{Repeat, RepeatPolicy};
+use crate::semdata::Track;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+// Key the app state is saved under.
+const STATE_KEY: &str = "cpi-calculator-state";
+// Bump when SavedState changes shape; older saves are then ignored.
+const STATE_VERSION: u32 = 1;
+
+// A string key-value store. The web app uses the browser's localStorage and
+// the desktop app one file per key; other frontends can plug in their own.
+pub trait Storage {
+    fn get(&self, key: &str) -> Option<String>;
+    fn set(&self, key: &str, value: &str);
+    fn remove(&self, key: &str);
+}
+
+// In-memory backend, for native builds and anywhere nothing should persist.
+#[derive(Default)]
+pub struct MemoryStorage {
+    items: RefCell<HashMap<String, String>>,
+}
+
+impl Storage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.items.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        self.items
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+    }
+
+    fn remove(&self, key: &str) {
+        self.items.borrow_mut().remove(key);
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedTranscriptRow {
+    pub track: Option<Track>,
+    pub grades: Vec<String>,
+    #[serde(default)]
+    pub electives: Vec<Option<String>>,
+    #[serde(default)]
+    pub repeats: Vec<Repeat>,
+}
+
+// Inputs of the target CPI planner: the semester just completed with the CPI
+// after it, and the later semester with the CPI wanted by then.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedTarget {
+    pub from_sem: Option<u8>,
+    pub from_track: Option<Track>,
+    pub current_cpi: String,
+    pub to_sem: Option<u8>,
+    pub to_track: Option<Track>,
+    pub target_cpi: String,
+}
+
+// The user's inputs, as kept between visits. Values are stored as entered so
+// a half-typed CPI comes back exactly as it was left.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedState {
+    pub version: u32,
+    pub calc_type: Option<i32>,
+    pub programme: Option<String>,
+    pub batch: Option<u16>,
+    pub sem_no: Option<u8>,
+    pub track: Option<Track>,
+    pub calc_cpi_option: Option<i32>,
+    pub grades: Vec<String>,
+    #[serde(default)]
+    pub electives: Vec<Option<String>>,
+    pub prev_cpi: String,
+    pub current_spi: String,
+    pub transcript: Vec<SavedTranscriptRow>,
+    #[serde(default)]
+    pub repeat_policy: RepeatPolicy,
+    #[serde(default)]
+    pub path_tracks: BTreeMap<u8, Track>,
+    #[serde(default)]
+    pub path_electives: BTreeMap<u8, Vec<Option<String>>>,
+    #[serde(default)]
+    pub target: SavedTarget,
+}
+
+pub fn save_state(storage: &dyn Storage, state: &SavedState) {
+    let state = SavedState {
+        version: STATE_VERSION,
+        ..state.clone()
+    };
+    if let Ok(json) = serde_json::to_string(&state) {
+        storage.set(STATE_KEY, &json);
+    }
+}
+
+// The last saved state, or None if nothing usable was saved.
+pub fn load_state(storage: &dyn Storage) -> Option<SavedState> {
+    let state: SavedState = serde_json::from_str(&storage.get(STATE_KEY)?).ok()?;
+    (state.version == STATE_VERSION).then_some(state)
+}
+
+pub fn clear_state(storage: &dyn Storage) {
+    storage.remove(STATE_KEY);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_state() -> SavedState {
+        SavedState {
+            calc_type: Some(2),
+            programme: Some("cse".to_string()),
+            batch: Some(2021),
+            sem_no: Some(7),
+            track: Some(Track::Project),
+            grades: vec!["AA".to_string(), String::new()],
+            electives: vec![None, Some("CS432".to_string())],
+            prev_cpi: "8.".to_string(),
+            transcript: vec![SavedTranscriptRow {
+                track: None,
+                grades: vec!["AB".to_string()],
+                ..Default::default()
+            }],
+            repeat_policy: RepeatPolicy::Average,
+            path_tracks: BTreeMap::from([(7, Track::Project)]),
+            path_electives: BTreeMap::from([(7, vec![None, None, Some("CS441".to_string())])]),
+            target: SavedTarget {
+                from_sem: Some(6),
+                current_cpi: "8.2".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn saved_state_comes_back_as_left() {
+        let storage = MemoryStorage::default();
+        assert_eq!(load_state(&storage), None);
+
+        let state = sample_state();
+        save_state(&storage, &state);
+        let loaded = load_state(&storage).unwrap();
+        assert_eq!(loaded.version, STATE_VERSION);
+        assert_eq!(
+            SavedState {
+                version: 0,
+                ..loaded
+            },
+            state
+        );
+
+        clear_state(&storage);
+        assert_eq!(load_state(&storage), None);
+    }
+
+    #[test]
+    fn saves_from_another_version_are_ignored() {
+        let storage = MemoryStorage::default();
+        save_state(&storage, &sample_state());
+        let json = storage.get(STATE_KEY).unwrap();
+        let old = json.replacen(
+            &format!("\"version\":{}", STATE_VERSION),
+            "\"version\":0",
+            1,
+        );
+        assert_ne!(old, json);
+        storage.set(STATE_KEY, &old);
+        assert_eq!(load_state(&storage), None);
+
+        // Nor is anything that does not parse
+        storage.set(STATE_KEY, "{\"version\":1");
+        assert_eq!(load_state(&storage), None);
+    }
+}
@@ -0,0 +1,761 @@
+use crate::file_storage::FileStorage;
+use cpi_core::form::{self, FormError, SemesterForm};
+use cpi_core::functions::{self, CalcError};
+use cpi_core::grades;
+use cpi_core::semdata::{self, Curriculum, Semester, SemesterId, Track};
+use cpi_core::storage::{self, SavedState, Storage};
+use eframe::egui;
+use std::collections::{BTreeMap, HashMap};
+
+// Key the window zoom is saved under, next to the app state.
+const SCALE_KEY: &str = "cpi-calculator-scale";
+
+// The SPI/CPI calculator of the web app, drawn with egui. Inputs are kept as
+// entered, in the same shape as the web app's, and saved the same way.
+pub struct DesktopApp {
+    calc_type: Option<i32>,    // 0 for SPI, 1 for CPI
+    programme: Option<String>, // Programme id, e.g. "cse"
+    batch: Option<u16>,        // Admission year, picks the curriculum version
+    sem_no: Option<u8>,
+    track: Option<Track>, // For semesters offered in several tracks (7/8)
+    path_tracks: BTreeMap<u8, Track>, // Tracks taken in earlier semesters, for the credits CPI is weighted by
+    path_electives: BTreeMap<u8, Vec<Option<String>>>, // Electives taken in them, per course slot
+    done_1: bool,                     // Tracks if initial semester selection is done
+    sem_info: Option<Semester>,
+    grades: Vec<String>,
+    electives: Vec<Option<String>>, // Elective bound to each placeholder slot, parallel to grades
+    calc_cpi_option: Option<i32>,   // 1 or 2
+    cpi_op1_var1_str: String,
+    cpi_op2_var1_str: String,
+    cpi_op2_var2_str: String,
+    calculated_spi: Option<f32>,
+    calculated_cpi: Option<f32>,
+    error_message: Option<String>,
+    course_errors: HashMap<usize, String>, // Per-course grade messages, by course index
+    show_grade_table: bool,
+    show_action_bar: bool,
+    storage: Box<dyn Storage>,
+    saved: SavedState, // Inputs as last saved; results are cleared when they change
+    zoom: f32,         // Zoom factor as last saved
+}
+
+impl DesktopApp {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        // Fall back to memory if there is nowhere to save (no config directory)
+        let storage: Box<dyn Storage> = match FileStorage::open() {
+            Some(files) => Box::new(files),
+            None => Box::new(storage::MemoryStorage::default()),
+        };
+        let zoom = storage
+            .get(SCALE_KEY)
+            .and_then(|s| s.trim().parse::<f32>().ok())
+            .filter(|z| (0.2..=5.0).contains(z))
+            .unwrap_or(1.0);
+        cc.egui_ctx.set_zoom_factor(zoom);
+        let saved = storage::load_state(storage.as_ref());
+        let mut app = Self {
+            calc_type: None,
+            programme: None,
+            batch: None,
+            sem_no: None,
+            track: None,
+            path_tracks: BTreeMap::new(),
+            path_electives: BTreeMap::new(),
+            done_1: false,
+            sem_info: None,
+            grades: Vec::new(),
+            electives: Vec::new(),
+            calc_cpi_option: None,
+            cpi_op1_var1_str: String::new(),
+            cpi_op2_var1_str: String::new(),
+            cpi_op2_var2_str: String::new(),
+            calculated_spi: None,
+            calculated_cpi: None,
+            error_message: None,
+            course_errors: HashMap::new(),
+            show_grade_table: false,
+            show_action_bar: true,
+            storage,
+            saved: SavedState::default(),
+            zoom,
+        };
+        if let Some(saved) = saved {
+            app.restore(saved);
+        }
+        app.saved = app.snapshot();
+        app
+    }
+
+    // Inputs worth keeping between runs
+    fn snapshot(&self) -> SavedState {
+        let prev_cpi = match self.calc_cpi_option {
+            Some(2) => &self.cpi_op2_var1_str,
+            _ => &self.cpi_op1_var1_str,
+        };
+        SavedState {
+            calc_type: self.calc_type,
+            programme: self.programme.clone(),
+            batch: self.batch,
+            sem_no: self.sem_no,
+            track: self.track,
+            calc_cpi_option: self.calc_cpi_option,
+            grades: self.grades.clone(),
+            electives: self.electives.clone(),
+            prev_cpi: prev_cpi.clone(),
+            current_spi: self.cpi_op2_var2_str.clone(),
+            path_tracks: self.path_tracks.clone(),
+            path_electives: self.path_electives.clone(),
+            ..Default::default()
+        }
+    }
+
+    // Puts back a saved state, dropping anything the current curricula no
+    // longer match. Calculations only the web app offers are not restored.
+    fn restore(&mut self, saved: SavedState) {
+        if !matches!(saved.calc_type, Some(0) | Some(1)) {
+            return;
+        }
+        self.calc_type = saved.calc_type;
+        self.programme = saved
+            .programme
+            .filter(|p| semdata::get_programme(p).is_some());
+        self.batch = saved.batch;
+        if self.curriculum().is_none() {
+            self.batch = None;
+            return;
+        }
+        self.sem_no = saved.sem_no;
+        self.track = saved.track;
+        self.calc_cpi_option = saved.calc_cpi_option;
+        self.path_tracks = saved.path_tracks;
+        self.path_electives = saved.path_electives;
+
+        // Reopen the saved semester as if 'Done' had been clicked
+        if self.sem_no.is_some() {
+            self.load_semester_data();
+            if self.sem_info.is_some() {
+                self.done_1 = true;
+                if saved.grades.len() == self.grades.len() {
+                    self.grades = saved.grades;
+                }
+                if saved.electives.len() == self.electives.len()
+                    && self.electives_fit(&saved.electives)
+                {
+                    self.electives = saved.electives;
+                }
+            }
+            self.error_message = None;
+        }
+
+        match self.calc_cpi_option {
+            Some(2) => self.cpi_op2_var1_str = saved.prev_cpi,
+            _ => self.cpi_op1_var1_str = saved.prev_cpi,
+        }
+        self.cpi_op2_var2_str = saved.current_spi;
+    }
+
+    // Saves changed inputs and zoom. Like any edit in the web app, a change
+    // clears the last results and errors.
+    fn persist(&mut self, ctx: &egui::Context) {
+        let state = self.snapshot();
+        if state != self.saved {
+            self.calculated_spi = None;
+            self.calculated_cpi = None;
+            self.error_message = None;
+            self.course_errors.clear();
+            storage::save_state(self.storage.as_ref(), &state);
+            self.saved = state;
+        }
+        let zoom = ctx.zoom_factor();
+        if zoom != self.zoom {
+            self.storage.set(SCALE_KEY, &zoom.to_string());
+            self.zoom = zoom;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.calc_type = None;
+        self.programme = None;
+        self.batch = None;
+        self.sem_no = None;
+        self.track = None;
+        self.path_tracks.clear();
+        self.path_electives.clear();
+        self.done_1 = false;
+        self.show_grade_table = false;
+        self.reset_semester_dependent_state();
+        storage::clear_state(self.storage.as_ref());
+        // Nothing to save until the next edit
+        self.saved = self.snapshot();
+    }
+
+    // Helper function to reset state dependent on semester selection
+    fn reset_semester_dependent_state(&mut self) {
+        self.sem_info = None;
+        self.grades = Vec::new();
+        self.electives = Vec::new();
+        self.calculated_spi = None;
+        self.calculated_cpi = None;
+        self.calc_cpi_option = None;
+        self.cpi_op1_var1_str = String::new();
+        self.cpi_op2_var1_str = String::new();
+        self.cpi_op2_var2_str = String::new();
+        self.error_message = None;
+        self.course_errors.clear();
+    }
+
+    // Curriculum for the selected programme and batch
+    fn curriculum(&self) -> Option<&'static Curriculum> {
+        semdata::get_curriculum(self.programme.as_deref()?, self.batch?)
+    }
+
+    fn available_tracks(&self, sem: u8) -> Vec<Track> {
+        self.curriculum().map(|c| c.tracks(sem)).unwrap_or_default()
+    }
+
+    // Combined semester number and track
+    fn semester_id(&self) -> Option<SemesterId> {
+        self.sem_no.map(|number| SemesterId {
+            number,
+            track: self.track,
+        })
+    }
+
+    // Whether `electives` are valid choices for the selected semester's slots
+    fn electives_fit(&self, electives: &[Option<String>]) -> bool {
+        match (self.curriculum(), self.semester_id()) {
+            (Some(curriculum), Some(id)) => {
+                functions::resolve_semester(curriculum, id, electives).is_ok()
+            }
+            _ => false,
+        }
+    }
+
+    // The selected semester with the chosen electives filled in
+    fn resolved_semester(&self) -> Option<Semester> {
+        let info = self.sem_info.as_ref()?;
+        let curriculum = self.curriculum()?;
+        Some(
+            functions::resolve_semester(curriculum, info.id, &self.electives)
+                .unwrap_or_else(|_| info.clone()),
+        )
+    }
+
+    fn load_semester_data(&mut self) {
+        if let (Some(programme), Some(batch), Some(sem_id)) =
+            (&self.programme, self.batch, self.semester_id())
+        {
+            match semdata::get_semesters(programme, batch, sem_id) {
+                Some(info) => {
+                    self.grades = vec![String::new(); info.course_code.len()];
+                    self.electives = vec![None; info.course_code.len()];
+                    self.sem_info = Some(info);
+                    self.error_message = None;
+                }
+                None => {
+                    self.sem_info = None;
+                    self.grades = Vec::new();
+                    self.electives = Vec::new();
+                    self.error_message =
+                        Some(format!("Could not find data for semester {}", sem_id));
+                }
+            }
+        }
+    }
+
+    fn set_done(&mut self) {
+        self.done_1 = false;
+        if self.programme.is_none() {
+            self.error_message = Some("Please select a programme first.".to_string());
+        } else if self.batch.is_none() {
+            self.error_message = Some("Please select your batch first.".to_string());
+        } else if let Some(sem) = self.sem_no {
+            if !self.available_tracks(sem).is_empty() && self.track.is_none() {
+                self.error_message = Some(format!("Please select a track for Semester {}.", sem));
+            } else {
+                self.done_1 = true;
+                self.load_semester_data();
+            }
+        } else {
+            self.error_message = Some("Please select a semester first.".to_string());
+        }
+    }
+
+    fn should_show_grade_input(&self) -> bool {
+        if !self.done_1 || self.sem_info.is_none() {
+            return false;
+        }
+        match self.calc_type {
+            Some(0) => true,
+            Some(1) => self.sem_no == Some(1) || self.calc_cpi_option == Some(1),
+            _ => false,
+        }
+    }
+
+    // --- Calculation Logic ---
+    fn calculate(&mut self) {
+        self.calculated_spi = None;
+        self.calculated_cpi = None;
+        self.error_message = None;
+        self.course_errors.clear();
+
+        let (programme, batch, sem_id) =
+            match (self.programme.as_deref(), self.batch, self.semester_id()) {
+                (Some(p), Some(b), Some(s)) if self.done_1 => (p, b, s),
+                _ => {
+                    self.error_message =
+                        Some("Please select semester/options and click 'Done' first.".to_string());
+                    return;
+                }
+            };
+        let prev_cpi = match self.calc_cpi_option {
+            Some(2) => &self.cpi_op2_var1_str,
+            _ => &self.cpi_op1_var1_str,
+        };
+        let inputs = SemesterForm {
+            programme,
+            batch,
+            semester: sem_id,
+            grades: &self.grades,
+            electives: &self.electives,
+            cpi: self.calc_type == Some(1),
+            cpi_option: self.calc_cpi_option,
+            prev_cpi,
+            current_spi: &self.cpi_op2_var2_str,
+            path_tracks: &self.path_tracks,
+            path_electives: &self.path_electives,
+        };
+        match inputs.calculate(grades::default_scale()) {
+            Ok(result) => {
+                self.calculated_spi = Some(result.spi);
+                self.calculated_cpi = result.cpi;
+            }
+            Err(FormError::Grades(errors)) => {
+                self.course_errors = errors
+                    .iter()
+                    .map(|(i, err)| (*i, form::course_message(err)))
+                    .collect();
+                self.error_message = Some("Please fix the grades marked below.".to_string());
+            }
+            Err(FormError::PrevCpi(_)) => {
+                self.error_message = Some(format!(
+                    "Invalid previous CPI value entered for Option {}.",
+                    self.calc_cpi_option.unwrap_or(1)
+                ))
+            }
+            Err(FormError::CurrentSpi(_)) => {
+                self.error_message =
+                    Some("Invalid current SPI value entered for Option 2.".to_string())
+            }
+            Err(FormError::NoCpiOption) => {
+                self.error_message = Some("Please select a CPI calculation option.".to_string())
+            }
+            Err(FormError::Calc(err)) => self.show_calc_error(err),
+        }
+    }
+
+    // Shows a failed calculation, next to the course row when it names one.
+    fn show_calc_error(&mut self, err: CalcError) {
+        match err.course() {
+            Some(index) => {
+                self.course_errors.insert(index, form::course_message(&err));
+                self.error_message = Some("Please fix the grades marked below.".to_string());
+            }
+            None => self.error_message = Some(form::error_message(&err)),
+        }
+    }
+
+    // --- Views ---
+
+    fn view_menu(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        egui::MenuBar::new().ui(ui, |ui| {
+            ui.menu_button("File", |ui| {
+                if ui.button("Reset").clicked() {
+                    self.reset();
+                }
+                if ui.button("Quit").clicked() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            });
+            ui.menu_button("View", |ui| {
+                // Zoom is the scale setting; it is saved between runs
+                egui::gui_zoom::zoom_menu_buttons(ui);
+                ui.separator();
+                ui.checkbox(&mut self.show_action_bar, "Show Action Bar");
+                ui.checkbox(&mut self.show_grade_table, "Show Grade Table");
+            });
+        });
+    }
+
+    fn view_action_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = if self.show_grade_table {
+                "Hide Grade Table"
+            } else {
+                "Show Grade Table"
+            };
+            if ui.button(label).clicked() {
+                self.show_grade_table = !self.show_grade_table;
+            }
+            if ui.button("Reset").clicked() {
+                self.reset();
+            }
+        });
+    }
+
+    fn view_grade_table(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Grade Point Table")
+            .open(&mut self.show_grade_table)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("grade_table").striped(true).show(ui, |ui| {
+                    ui.strong("Grade");
+                    ui.strong("Points");
+                    ui.end_row();
+                    for g in grades::default_scale().grades() {
+                        ui.label(&g.letter);
+                        ui.label(g.points.to_string());
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    fn view_inputs(&mut self, ui: &mut egui::Ui) {
+        ui.heading("CPI / SPI Calculator");
+        ui.separator();
+
+        let mut calc_type = self.calc_type;
+        egui::ComboBox::from_label("Calculation")
+            .selected_text(match calc_type {
+                Some(0) => "Calculate SPI",
+                Some(1) => "Calculate CPI",
+                _ => "Select",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut calc_type, Some(0), "Calculate SPI");
+                ui.selectable_value(&mut calc_type, Some(1), "Calculate CPI");
+            });
+        if calc_type != self.calc_type {
+            self.calc_type = calc_type;
+            self.reset_semester_dependent_state();
+            self.done_1 = false;
+        }
+        if self.calc_type.is_none() {
+            return;
+        }
+
+        self.view_semester_select(ui);
+        if ui.button("Done").clicked() {
+            self.set_done();
+        }
+        if let (true, Some(programme), Some(batch), Some(sem_id)) = (
+            self.done_1,
+            self.programme.as_deref().and_then(semdata::get_programme),
+            self.batch,
+            self.semester_id(),
+        ) {
+            ui.label(format!(
+                "Selected: {} ({} batch), Semester {}",
+                programme.name, batch, sem_id
+            ));
+        }
+        ui.separator();
+
+        if self.done_1 && self.calc_type == Some(1) && self.sem_no.is_some_and(|s| s > 1) {
+            self.view_cpi_options(ui);
+        }
+        if self.should_show_grade_input() {
+            self.view_grade_input(ui);
+        }
+        if self.done_1
+            && (self.should_show_grade_input() || self.calc_cpi_option == Some(2))
+            && ui.button("Calculate").clicked()
+        {
+            self.calculate();
+        }
+
+        if let Some(spi) = self.calculated_spi {
+            ui.label(egui::RichText::new(format!("Calculated SPI: {:.3}", spi)).strong());
+        }
+        if let Some(cpi) = self.calculated_cpi {
+            ui.label(egui::RichText::new(format!("Calculated CPI: {:.3}", cpi)).strong());
+        }
+        if let Some(err) = &self.error_message {
+            ui.colored_label(ui.visuals().error_fg_color, err);
+        }
+    }
+
+    fn view_semester_select(&mut self, ui: &mut egui::Ui) {
+        let mut programme = self.programme.clone();
+        egui::ComboBox::from_label("Programme")
+            .selected_text(
+                programme
+                    .as_deref()
+                    .and_then(semdata::get_programme)
+                    .map_or("Select", |p| p.name.as_str()),
+            )
+            .show_ui(ui, |ui| {
+                for p in semdata::programmes() {
+                    ui.selectable_value(&mut programme, Some(p.id.clone()), &p.name);
+                }
+            });
+        if programme != self.programme {
+            self.programme = programme;
+            // Keep the batch only if the new programme has a curriculum for it
+            if self.curriculum().is_none() {
+                self.batch = None;
+            }
+            self.path_electives.clear(); // Catalogs differ between curricula
+            self.done_1 = false;
+            self.reset_semester_dependent_state();
+        }
+        let programme = match self.programme.as_deref().and_then(semdata::get_programme) {
+            Some(p) => p,
+            None => return,
+        };
+
+        let mut batch = self.batch;
+        egui::ComboBox::from_label("Batch (Admission Year)")
+            .selected_text(batch.map_or("Select".to_string(), |b| b.to_string()))
+            .show_ui(ui, |ui| {
+                for year in programme.batches(semdata::current_year()).into_iter().rev() {
+                    ui.selectable_value(&mut batch, Some(year), year.to_string());
+                }
+            });
+        if batch != self.batch {
+            self.batch = batch;
+            self.path_electives.clear();
+            self.done_1 = false;
+            self.reset_semester_dependent_state();
+        }
+        let curriculum = match self.curriculum() {
+            Some(c) => c,
+            None => return,
+        };
+
+        let mut sem_no = self.sem_no;
+        egui::ComboBox::from_label("Semester")
+            .selected_text(sem_no.map_or("Select".to_string(), |s| format!("Semester {}", s)))
+            .show_ui(ui, |ui| {
+                for number in curriculum.semester_numbers() {
+                    ui.selectable_value(&mut sem_no, Some(number), format!("Semester {}", number));
+                }
+            });
+        if sem_no != self.sem_no {
+            self.sem_no = sem_no;
+            self.track = None;
+            self.done_1 = false;
+            self.reset_semester_dependent_state();
+        }
+
+        let tracks = self
+            .sem_no
+            .map(|sem| curriculum.tracks(sem))
+            .unwrap_or_default();
+        if !tracks.is_empty() {
+            let mut track = self.track;
+            egui::ComboBox::from_label(format!("Track (Sem {})", self.sem_no.unwrap_or_default()))
+                .selected_text(track.map_or("Select", |t| t.label()))
+                .show_ui(ui, |ui| {
+                    for t in tracks {
+                        ui.selectable_value(&mut track, Some(t), t.label());
+                    }
+                });
+            if track != self.track {
+                self.track = track;
+                self.done_1 = false;
+                self.reset_semester_dependent_state();
+            }
+        }
+    }
+
+    fn view_cpi_options(&mut self, ui: &mut egui::Ui) {
+        let sem = self.sem_no.unwrap_or(0);
+        ui.heading("Calculate CPI Options");
+        self.view_path_tracks(ui, sem);
+
+        let mut option = self.calc_cpi_option;
+        ui.radio_value(
+            &mut option,
+            Some(1),
+            format!(
+                "Calculate CPI for Sem {} using previous CPI (Sem {}) and current semester grades",
+                sem,
+                sem - 1
+            ),
+        );
+        ui.radio_value(&mut option, Some(2), format!("Calculate CPI for Sem {} using previous CPI (Sem {}) and current semester SPI (Sem {})", sem, sem - 1, sem));
+        if option != self.calc_cpi_option {
+            // Inputs of the other option do not carry over
+            self.calc_cpi_option = option;
+            self.cpi_op1_var1_str = String::new();
+            self.cpi_op2_var1_str = String::new();
+            self.cpi_op2_var2_str = String::new();
+            self.grades = vec![String::new(); self.grades.len()];
+        }
+
+        match self.calc_cpi_option {
+            Some(1) => number_input(
+                ui,
+                &format!("Enter CPI up to Semester {}:", sem - 1),
+                &mut self.cpi_op1_var1_str,
+                "e.g., 8.5",
+            ),
+            Some(2) => {
+                number_input(
+                    ui,
+                    &format!("Enter CPI up to Semester {}:", sem - 1),
+                    &mut self.cpi_op2_var1_str,
+                    "e.g., 8.5",
+                );
+                number_input(
+                    ui,
+                    &format!("Enter SPI of Semester {}:", sem),
+                    &mut self.cpi_op2_var2_str,
+                    "e.g., 9.0",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    // For each semester before `sem`, a track picker if it had several
+    // tracks and an elective picker for each placeholder slot, so CPI is
+    // weighted by the credits actually taken
+    fn view_path_tracks(&mut self, ui: &mut egui::Ui, sem: u8) {
+        let curriculum = match self.curriculum() {
+            Some(c) => c,
+            None => return,
+        };
+        for number in 1..sem {
+            let tracks = curriculum.tracks(number);
+            if !tracks.is_empty() {
+                let mut track = self.path_tracks.get(&number).copied();
+                egui::ComboBox::from_label(format!("Track taken in Semester {}", number))
+                    .selected_text(track.map_or("Select", |t| t.label()))
+                    .show_ui(ui, |ui| {
+                        for t in tracks {
+                            ui.selectable_value(&mut track, Some(t), t.label());
+                        }
+                    });
+                if let Some(track) = track {
+                    if self.path_tracks.insert(number, track) != Some(track) {
+                        // The slots differ between tracks
+                        self.path_electives.remove(&number);
+                    }
+                }
+            }
+
+            let path = form::path_before(curriculum, number + 1, &self.path_tracks);
+            let semester = match curriculum.semester(path[number as usize - 1]) {
+                Some(s) => s,
+                None => continue,
+            };
+            for index in 0..semester.course_code.len() {
+                let options = curriculum.electives_for(semester.id, index);
+                if options.is_empty() {
+                    continue;
+                }
+                let current = form::path_elective(&self.path_electives, number, index);
+                let mut choice = current.clone();
+                egui::ComboBox::new(
+                    ("path_elective", number, index),
+                    format!("Semester {} {}", number, semester.course_name[index]),
+                )
+                .selected_text(choice.clone().unwrap_or_else(|| "Not chosen".to_string()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut choice, None, "Not chosen");
+                    for e in options {
+                        ui.selectable_value(
+                            &mut choice,
+                            Some(e.code.clone()),
+                            format!("{} - {} (Credits: {})", e.code, e.name, e.credit),
+                        );
+                    }
+                });
+                if choice != current {
+                    form::choose_path_elective(&mut self.path_electives, number, index, choice);
+                }
+            }
+        }
+    }
+
+    fn view_grade_input(&mut self, ui: &mut egui::Ui) {
+        let (sem, curriculum) = match (self.resolved_semester(), self.curriculum()) {
+            (Some(sem), Some(curriculum)) => (sem, curriculum),
+            _ => return,
+        };
+        ui.heading(format!("Enter Grades for Semester {}", sem.id));
+        ui.label(format!("Total Credits: {}", sem.total_credit));
+        for index in 0..sem.course_code.len() {
+            ui.group(|ui| {
+                ui.label(format!(
+                    "{} - {} (Credits: {})",
+                    sem.course_code[index], sem.course_name[index], sem.course_credit[index]
+                ));
+                let options = curriculum.electives_for(sem.id, index);
+                if !options.is_empty() {
+                    let mut choice = self.electives[index].clone();
+                    egui::ComboBox::new(("elective", index), "Elective Course")
+                        .selected_text(choice.clone().unwrap_or_else(|| "Not chosen".to_string()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut choice, None, "Not chosen");
+                            for e in options {
+                                ui.selectable_value(
+                                    &mut choice,
+                                    Some(e.code.clone()),
+                                    format!("{} - {} (Credits: {})", e.code, e.name, e.credit),
+                                );
+                            }
+                        });
+                    self.electives[index] = choice;
+                }
+                let grade = &mut self.grades[index];
+                egui::ComboBox::new(("grade", index), "Grade")
+                    .selected_text(if grade.is_empty() {
+                        "Select".to_string()
+                    } else {
+                        grade.clone()
+                    })
+                    .show_ui(ui, |ui| {
+                        for g in grades::default_scale().grades() {
+                            ui.selectable_value(
+                                grade,
+                                g.letter.clone(),
+                                format!("{} ({})", g.letter, g.points),
+                            );
+                        }
+                    });
+                if let Some(err) = self.course_errors.get(&index) {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+            });
+        }
+    }
+}
+
+impl eframe::App for DesktopApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("menu").show(ctx, |ui| self.view_menu(ctx, ui));
+        if self.show_action_bar {
+            egui::TopBottomPanel::bottom("action_bar").show(ctx, |ui| self.view_action_bar(ui));
+        }
+        if self.show_grade_table {
+            self.view_grade_table(ctx);
+        }
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| self.view_inputs(ui));
+        });
+        self.persist(ctx);
+    }
+}
+
+fn number_input(ui: &mut egui::Ui, label: &str, value: &mut String, hint: &str) {
+    ui.label(label);
+    ui.add(
+        egui::TextEdit::singleline(value)
+            .hint_text(hint)
+            .desired_width(120.0),
+    );
+}
@@ -0,0 +1,44 @@
+use cpi_core::storage::Storage;
+use std::fs;
+use std::path::PathBuf;
+
+// Name of the folder created in the user's config directory.
+const APP_DIR: &str = "cpi-calculator";
+
+// Keeps each key in its own file under a directory, e.g.
+// ~/.config/cpi-calculator/cpi-calculator-state on Linux. Like the browser
+// backend, writes are best effort: a read-only disk just means nothing is saved.
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    // None if the platform has no per-user config directory.
+    pub fn open() -> Option<Self> {
+        Some(FileStorage {
+            dir: dirs::config_dir()?.join(APP_DIR),
+        })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+}
+
+impl Storage for FileStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.path(key)).ok()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        // Write a sibling file first so a crash mid-write keeps the old value
+        let tmp = self.path(&format!("{}.tmp", key));
+        let _ = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&tmp, value))
+            .and_then(|_| fs::rename(&tmp, self.path(key)));
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key));
+    }
+}
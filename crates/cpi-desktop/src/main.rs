@@ -0,0 +1,18 @@
+// Native desktop frontend: the calculator in an eframe window, with inputs
+// and the zoom level saved to the user's config directory.
+mod app;
+mod file_storage;
+
+fn main() -> eframe::Result {
+    let options = eframe::NativeOptions {
+        viewport: eframe::egui::ViewportBuilder::default()
+            .with_title("CPI / SPI Calculator")
+            .with_inner_size([720.0, 820.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "CPI / SPI Calculator",
+        options,
+        Box::new(|cc| Ok(Box::new(app::DesktopApp::new(cc)))),
+    )
+}
@@ -0,0 +1,635 @@
+use cpi_core::form::{self, FormError, SemesterForm};
+use cpi_core::functions::{self, CalcError};
+use cpi_core::grades;
+use cpi_core::semdata::{self, Curriculum, Semester, SemesterId, Track};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+
+// One focusable row of the form. Rows only appear once the choices above them
+// are made, in the order the web app reveals them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Calculation,
+    Programme,
+    Batch,
+    Semester,
+    Track,
+    PathTrack(u8),           // Track taken in an earlier semester
+    PathElective(u8, usize), // Elective taken in an earlier semester, by course index
+    CpiOption,
+    PrevCpi,
+    CurrentSpi,
+    Elective(usize), // Elective bound to the placeholder slot at this course index
+    Grade(usize),
+}
+
+// What the inputs add up to so far. Worked out afresh for every frame, so
+// results follow each keystroke without a Calculate step.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    pub spi: Option<f32>,
+    pub cpi: Option<f32>,
+    pub message: Option<String>, // What is still missing, or why the calculation failed
+    pub course_errors: HashMap<usize, String>, // Per-course grade messages, by course index
+}
+
+// The SPI/CPI calculator of the web app as a terminal form. Inputs are kept as
+// entered, in the same shape as the other frontends'.
+#[derive(Default)]
+pub struct App {
+    pub calc_type: Option<i32>,    // 0 for SPI, 1 for CPI
+    pub programme: Option<String>, // Programme id, e.g. "cse"
+    pub batch: Option<u16>,        // Admission year, picks the curriculum version
+    pub sem_no: Option<u8>,
+    pub track: Option<Track>, // For semesters offered in several tracks (7/8)
+    pub path_tracks: BTreeMap<u8, Track>, // Tracks taken in earlier semesters, for the credits CPI is weighted by
+    pub path_electives: BTreeMap<u8, Vec<Option<String>>>, // Electives taken in earlier semesters, by course index
+    pub sem_info: Option<Semester>, // Loaded as soon as the semester (and track) is chosen
+    pub grades: Vec<String>,
+    pub electives: Vec<Option<String>>, // Elective bound to each placeholder slot, parallel to grades
+    pub calc_cpi_option: Option<i32>,   // 1 or 2
+    pub prev_cpi: String,
+    pub current_spi: String, // Option 2 only
+    pub focus: usize,        // Index into `fields()`
+    pub show_grade_table: bool,
+    pub quit: bool,
+}
+
+impl App {
+    fn reset(&mut self) {
+        *self = Self {
+            show_grade_table: self.show_grade_table,
+            ..Self::default()
+        };
+    }
+
+    // Helper function to reset state dependent on semester selection
+    fn reset_semester_dependent_state(&mut self) {
+        self.sem_info = None;
+        self.grades = Vec::new();
+        self.electives = Vec::new();
+        self.calc_cpi_option = None;
+        self.prev_cpi = String::new();
+        self.current_spi = String::new();
+    }
+
+    // Curriculum for the selected programme and batch
+    pub fn curriculum(&self) -> Option<&'static Curriculum> {
+        semdata::get_curriculum(self.programme.as_deref()?, self.batch?)
+    }
+
+    pub fn available_tracks(&self, sem: u8) -> Vec<Track> {
+        self.curriculum().map(|c| c.tracks(sem)).unwrap_or_default()
+    }
+
+    // Earlier semester `number` as taken on the chosen path
+    pub fn path_semester(&self, number: u8) -> Option<&'static Semester> {
+        let curriculum = self.curriculum()?;
+        let id = *form::path_before(curriculum, number + 1, &self.path_tracks).last()?;
+        curriculum.semester(id)
+    }
+
+    // Combined semester number and track
+    fn semester_id(&self) -> Option<SemesterId> {
+        self.sem_no.map(|number| SemesterId {
+            number,
+            track: self.track,
+        })
+    }
+
+    // The selected semester with the chosen electives filled in
+    pub fn resolved_semester(&self) -> Option<Semester> {
+        let info = self.sem_info.as_ref()?;
+        let curriculum = self.curriculum()?;
+        Some(
+            functions::resolve_semester(curriculum, info.id, &self.electives)
+                .unwrap_or_else(|_| info.clone()),
+        )
+    }
+
+    // Loads the course list once the selection is complete; there is no
+    // 'Done' step as in the web app.
+    fn load_semester_data(&mut self) {
+        let complete = self
+            .sem_no
+            .is_some_and(|sem| self.track.is_some() || self.available_tracks(sem).is_empty());
+        if let (true, Some(programme), Some(batch), Some(sem_id)) =
+            (complete, &self.programme, self.batch, self.semester_id())
+        {
+            if let Some(info) = semdata::get_semesters(programme, batch, sem_id) {
+                self.grades = vec![String::new(); info.course_code.len()];
+                self.electives = vec![None; info.course_code.len()];
+                self.sem_info = Some(info);
+            }
+        }
+    }
+
+    // Called after any choice the course list depends on
+    fn selection_changed(&mut self) {
+        self.reset_semester_dependent_state();
+        self.load_semester_data();
+    }
+
+    pub fn should_show_grade_input(&self) -> bool {
+        if self.sem_info.is_none() {
+            return false;
+        }
+        match self.calc_type {
+            Some(0) => true,
+            Some(1) => self.sem_no == Some(1) || self.calc_cpi_option == Some(1),
+            _ => false,
+        }
+    }
+
+    // The rows currently on the form, top to bottom
+    pub fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![Field::Calculation];
+        if self.calc_type.is_none() {
+            return fields;
+        }
+        fields.push(Field::Programme);
+        if self.programme.is_none() {
+            return fields;
+        }
+        fields.push(Field::Batch);
+        if self.curriculum().is_none() {
+            return fields;
+        }
+        fields.push(Field::Semester);
+        let sem = match self.sem_no {
+            Some(sem) => sem,
+            None => return fields,
+        };
+        if !self.available_tracks(sem).is_empty() {
+            fields.push(Field::Track);
+        }
+        if self.sem_info.is_none() {
+            return fields;
+        }
+        if self.calc_type == Some(1) && sem > 1 {
+            for number in 1..sem {
+                if !self.available_tracks(number).is_empty() {
+                    fields.push(Field::PathTrack(number));
+                }
+                if let (Some(semester), Some(curriculum)) =
+                    (self.path_semester(number), self.curriculum())
+                {
+                    fields.extend(
+                        (0..semester.course_code.len())
+                            .filter(|&i| !curriculum.electives_for(semester.id, i).is_empty())
+                            .map(|i| Field::PathElective(number, i)),
+                    );
+                }
+            }
+            fields.push(Field::CpiOption);
+            match self.calc_cpi_option {
+                Some(1) => fields.push(Field::PrevCpi),
+                Some(2) => fields.extend([Field::PrevCpi, Field::CurrentSpi]),
+                _ => {}
+            }
+        }
+        if let (true, Some(info), Some(curriculum)) = (
+            self.should_show_grade_input(),
+            &self.sem_info,
+            self.curriculum(),
+        ) {
+            for index in 0..info.course_code.len() {
+                if !curriculum.electives_for(info.id, index).is_empty() {
+                    fields.push(Field::Elective(index));
+                }
+                fields.push(Field::Grade(index));
+            }
+        }
+        fields
+    }
+
+    pub fn focused(&self) -> Option<Field> {
+        self.fields().get(self.focus).copied()
+    }
+
+    // --- Keyboard ---
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Char('r') if ctrl => self.reset(),
+            KeyCode::Char('t') if ctrl => self.show_grade_table = !self.show_grade_table,
+            KeyCode::Down | KeyCode::Tab | KeyCode::Enter => self.focus += 1,
+            KeyCode::Up | KeyCode::BackTab => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Home => self.focus = 0,
+            KeyCode::End => self.focus = usize::MAX,
+            KeyCode::Right => self.change(true),
+            KeyCode::Left => self.change(false),
+            KeyCode::Backspace | KeyCode::Delete => self.erase(),
+            KeyCode::Char(c) if !ctrl => self.type_char(c),
+            _ => {}
+        }
+        // Rows come and go with the choices above them; stay on the form
+        self.focus = self.focus.min(self.fields().len() - 1);
+    }
+
+    // Steps the focused choice forwards or backwards
+    fn change(&mut self, forward: bool) {
+        match self.focused() {
+            Some(Field::Calculation) => {
+                let calc_type = cycle(&[Some(0), Some(1)], &self.calc_type, forward);
+                if calc_type != self.calc_type {
+                    self.calc_type = calc_type;
+                    self.selection_changed();
+                }
+            }
+            Some(Field::Programme) => {
+                let ids: Vec<Option<String>> = semdata::programmes()
+                    .iter()
+                    .map(|p| Some(p.id.clone()))
+                    .collect();
+                self.programme = cycle(&ids, &self.programme, forward);
+                // Keep the batch only if the new programme has a curriculum for it
+                if self.curriculum().is_none() {
+                    self.batch = None;
+                }
+                self.path_electives.clear();
+                self.selection_changed();
+            }
+            Some(Field::Batch) => {
+                let batches: Vec<Option<u16>> =
+                    match self.programme.as_deref().and_then(semdata::get_programme) {
+                        Some(programme) => programme
+                            .batches(semdata::current_year())
+                            .into_iter()
+                            .rev()
+                            .map(Some)
+                            .collect(),
+                        None => return,
+                    };
+                self.batch = cycle(&batches, &self.batch, forward);
+                self.path_electives.clear();
+                self.selection_changed();
+            }
+            Some(Field::Semester) => {
+                let numbers: Vec<Option<u8>> = self
+                    .curriculum()
+                    .map(|c| c.semester_numbers().into_iter().map(Some).collect())
+                    .unwrap_or_default();
+                self.sem_no = cycle(&numbers, &self.sem_no, forward);
+                self.track = None;
+                self.selection_changed();
+            }
+            Some(Field::Track) => {
+                let tracks: Vec<Option<Track>> = self
+                    .available_tracks(self.sem_no.unwrap_or(0))
+                    .into_iter()
+                    .map(Some)
+                    .collect();
+                self.track = cycle(&tracks, &self.track, forward);
+                self.selection_changed();
+            }
+            Some(Field::PathTrack(number)) => {
+                let tracks: Vec<Option<Track>> = self
+                    .available_tracks(number)
+                    .into_iter()
+                    .map(Some)
+                    .collect();
+                if let Some(track) =
+                    cycle(&tracks, &self.path_tracks.get(&number).copied(), forward)
+                {
+                    // The other track has other courses
+                    if self.path_tracks.insert(number, track) != Some(track) {
+                        self.path_electives.remove(&number);
+                    }
+                }
+            }
+            Some(Field::PathElective(number, index)) => {
+                let (semester, curriculum) = match (self.path_semester(number), self.curriculum()) {
+                    (Some(semester), Some(curriculum)) => (semester, curriculum),
+                    _ => return,
+                };
+                let mut choices = vec![None];
+                choices.extend(
+                    curriculum
+                        .electives_for(semester.id, index)
+                        .into_iter()
+                        .map(|e| Some(e.code.clone())),
+                );
+                let current = form::path_elective(&self.path_electives, number, index);
+                let choice = cycle(&choices, &current, forward);
+                form::choose_path_elective(&mut self.path_electives, number, index, choice);
+            }
+            Some(Field::CpiOption) => {
+                let option = cycle(&[Some(1), Some(2)], &self.calc_cpi_option, forward);
+                if option != self.calc_cpi_option {
+                    // Inputs of the other option do not carry over
+                    self.calc_cpi_option = option;
+                    self.prev_cpi = String::new();
+                    self.current_spi = String::new();
+                    self.grades = vec![String::new(); self.grades.len()];
+                }
+            }
+            Some(Field::Elective(index)) => {
+                let (info, curriculum) = match (&self.sem_info, self.curriculum()) {
+                    (Some(info), Some(curriculum)) => (info, curriculum),
+                    _ => return,
+                };
+                let mut choices = vec![None];
+                choices.extend(
+                    curriculum
+                        .electives_for(info.id, index)
+                        .into_iter()
+                        .map(|e| Some(e.code.clone())),
+                );
+                self.electives[index] = cycle(&choices, &self.electives[index], forward);
+            }
+            Some(Field::Grade(index)) => {
+                let letters: Vec<String> = grades::default_scale()
+                    .grades()
+                    .iter()
+                    .map(|g| g.letter.clone())
+                    .collect();
+                self.grades[index] = cycle(&letters, &self.grades[index], forward);
+            }
+            Some(Field::PrevCpi) | Some(Field::CurrentSpi) | None => {}
+        }
+    }
+
+    // Typing fills in grades and numbers
+    fn type_char(&mut self, c: char) {
+        match self.focused() {
+            Some(Field::PrevCpi) if c.is_ascii_digit() || c == '.' => self.prev_cpi.push(c),
+            Some(Field::CurrentSpi) if c.is_ascii_digit() || c == '.' => self.current_spi.push(c),
+            Some(Field::Grade(index)) if c.is_ascii_alphabetic() => {
+                let grade = &mut self.grades[index];
+                // Typing over a complete (or impossible) grade starts a new one
+                if !is_unfinished(grade) {
+                    grade.clear();
+                }
+                grade.push(c.to_ascii_uppercase());
+            }
+            _ => {}
+        }
+    }
+
+    fn erase(&mut self) {
+        match self.focused() {
+            Some(Field::PrevCpi) => {
+                self.prev_cpi.pop();
+            }
+            Some(Field::CurrentSpi) => {
+                self.current_spi.pop();
+            }
+            Some(Field::Grade(index)) => {
+                self.grades[index].pop();
+            }
+            Some(Field::Elective(index)) => self.electives[index] = None,
+            Some(Field::PathElective(number, index)) => {
+                form::choose_path_elective(&mut self.path_electives, number, index, None)
+            }
+            _ => {}
+        }
+    }
+
+    // --- Calculation Logic ---
+
+    pub fn evaluate(&self) -> Outcome {
+        let mut outcome = Outcome::default();
+        let (programme, batch, sem_id) =
+            match (self.programme.as_deref(), self.batch, self.semester_id()) {
+                (Some(p), Some(b), Some(s)) if self.sem_info.is_some() => (p, b, s),
+                _ => {
+                    outcome.message = Some(self.missing_selection());
+                    return outcome;
+                }
+            };
+        let inputs = SemesterForm {
+            programme,
+            batch,
+            semester: sem_id,
+            grades: &self.grades,
+            electives: &self.electives,
+            cpi: self.calc_type == Some(1),
+            cpi_option: self.calc_cpi_option,
+            prev_cpi: &self.prev_cpi,
+            current_spi: &self.current_spi,
+            path_tracks: &self.path_tracks,
+            path_electives: &self.path_electives,
+        };
+        let prev_cpi = || {
+            format!(
+                "your CPI up to Semester {}",
+                sem_id.number.saturating_sub(1)
+            )
+        };
+        let current_spi = || format!("your SPI of Semester {}", sem_id.number);
+        match inputs.calculate(grades::default_scale()) {
+            Ok(result) => {
+                outcome.spi = Some(result.spi);
+                outcome.cpi = result.cpi;
+            }
+            Err(FormError::Grades(errors)) => {
+                // Blank and half-typed rows are still being filled in; only
+                // flag what cannot become a grade
+                outcome.course_errors = errors
+                    .iter()
+                    .filter(|(i, _)| !is_unfinished(&self.grades[*i]))
+                    .map(|(i, err)| (*i, form::course_message(err)))
+                    .collect();
+                outcome.message = Some(if outcome.course_errors.is_empty() {
+                    format!(
+                        "{} of {} grades still to enter.",
+                        errors.len(),
+                        self.grades.len()
+                    )
+                } else {
+                    "Please fix the grades marked below.".to_string()
+                });
+            }
+            Err(FormError::PrevCpi(value)) => {
+                outcome.message = Some(number_message(&value, &prev_cpi()))
+            }
+            Err(FormError::CurrentSpi(value)) => {
+                outcome.message = Some(number_message(&value, &current_spi()))
+            }
+            Err(FormError::NoCpiOption) => {
+                outcome.message = Some("Please select a CPI calculation option.".to_string())
+            }
+            Err(FormError::Calc(err)) => outcome.show_calc_error(err),
+        }
+        outcome
+    }
+
+    // The first choice still to make, worded like the web app's prompts
+    fn missing_selection(&self) -> String {
+        if self.calc_type.is_none() {
+            "Please select a calculation first.".to_string()
+        } else if self.programme.is_none() {
+            "Please select a programme first.".to_string()
+        } else if self.batch.is_none() {
+            "Please select your batch first.".to_string()
+        } else if let Some(sem) = self.sem_no {
+            format!("Please select a track for Semester {}.", sem)
+        } else {
+            "Please select a semester first.".to_string()
+        }
+    }
+}
+
+impl Outcome {
+    // Shows a failed calculation, next to the course row when it names one.
+    fn show_calc_error(&mut self, err: CalcError) {
+        match err.course() {
+            Some(index) => {
+                self.course_errors.insert(index, form::course_message(&err));
+                self.message = Some("Please fix the grades marked below.".to_string());
+            }
+            None => self.message = Some(form::error_message(&err)),
+        }
+    }
+}
+
+// The option after (or before) `current` in `options`, wrapping around. From a
+// value not in the list it starts at the first (or last) option.
+fn cycle<T: Clone + PartialEq>(options: &[T], current: &T, forward: bool) -> T {
+    let len = options.len();
+    if len == 0 {
+        return current.clone();
+    }
+    let next = match options.iter().position(|o| o == current) {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None if forward => 0,
+        None => len - 1,
+    };
+    options[next].clone()
+}
+
+// Whether a grade is blank or the start of a longer letter, like the 'A' of 'AB'
+fn is_unfinished(grade: &str) -> bool {
+    let grade = grade.trim().to_ascii_uppercase();
+    grade.is_empty()
+        || grades::default_scale().grades().iter().any(|g| {
+            g.letter.len() > grade.len() && g.letter.to_ascii_uppercase().starts_with(&grade)
+        })
+}
+
+// Why a CPI/SPI field was not accepted; `what` names it
+fn number_message(value: &str, what: &str) -> String {
+    if value.trim().is_empty() {
+        format!("Enter {}.", what)
+    } else {
+        format!("'{}' is not a valid number for {}.", value, what)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    // Picks the first programme and newest batch, then semester `sem`, leaving
+    // the focus on the semester row
+    fn choose_semester(app: &mut App, sem: u8) {
+        for field in [Field::Programme, Field::Batch, Field::Semester] {
+            press(app, KeyCode::Down);
+            assert_eq!(app.focused(), Some(field));
+            press(app, KeyCode::Right);
+        }
+        for _ in 1..sem {
+            press(app, KeyCode::Right);
+        }
+        assert_eq!(app.sem_no, Some(sem));
+    }
+
+    // Moves down through the course rows below the focus, giving every
+    // course an AA and leaving the electives unchosen
+    fn grade_all_aa(app: &mut App) {
+        loop {
+            press(app, KeyCode::Down);
+            match app.focused() {
+                Some(Field::Grade(index)) if app.grades[index].is_empty() => type_text(app, "AA"),
+                Some(Field::Elective(_)) => {}
+                _ => break,
+            }
+        }
+    }
+
+    #[test]
+    fn focus_moves_through_the_rows_on_screen() {
+        let mut app = App::default();
+        assert_eq!(app.fields(), [Field::Calculation]);
+        // Nothing below the first choice yet, so the focus stays put
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.focused(), Some(Field::Calculation));
+
+        press(&mut app, KeyCode::Right);
+        assert_eq!(app.calc_type, Some(0));
+        choose_semester(&mut app, 1);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.focused(), Some(Field::Grade(0)));
+        press(&mut app, KeyCode::End);
+        assert_eq!(app.focused(), Some(Field::Grade(7)));
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.focused(), Some(Field::Grade(6)));
+        press(&mut app, KeyCode::Home);
+        assert_eq!(app.focused(), Some(Field::Calculation));
+
+        press(&mut app, KeyCode::Esc);
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn spi_follows_the_grades_typed() {
+        let mut app = App::default();
+        press(&mut app, KeyCode::Right);
+        choose_semester(&mut app, 1);
+        assert_eq!(app.evaluate().spi, None);
+
+        grade_all_aa(&mut app);
+        assert_eq!(app.evaluate().spi, Some(10.0));
+
+        // Backspace takes the last grade back to half-typed
+        press(&mut app, KeyCode::End);
+        press(&mut app, KeyCode::Backspace);
+        let outcome = app.evaluate();
+        assert_eq!(outcome.spi, None);
+        assert_eq!(
+            outcome.message.as_deref(),
+            Some("1 of 8 grades still to enter.")
+        );
+    }
+
+    #[test]
+    fn cpi_follows_the_previous_cpi_typed() {
+        let mut app = App::default();
+        // Backwards from no choice lands on the last one, CPI
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.calc_type, Some(1));
+        choose_semester(&mut app, 2);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.focused(), Some(Field::CpiOption));
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.focused(), Some(Field::PrevCpi));
+        type_text(&mut app, "8");
+        let courses = app.grades.len();
+        assert_eq!(
+            app.evaluate().message,
+            Some(format!("{} of {} grades still to enter.", courses, courses))
+        );
+
+        grade_all_aa(&mut app);
+        let outcome = app.evaluate();
+        assert_eq!(outcome.spi, Some(10.0));
+        // (8 * 46 + 10 * 44) / 90
+        assert!((outcome.cpi.unwrap() - 808.0 / 90.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,30 @@
+// Terminal frontend: the SPI/CPI calculator as a keyboard-driven form, with
+// results recomputed on every keystroke.
+mod app;
+mod ui;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+use std::io;
+
+fn main() -> io::Result<()> {
+    // Restores the terminal on panic as well as on the way out
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    let mut app = app::App::default();
+    while !app.quit {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+        if let Event::Key(key) = event::read()? {
+            // Windows also reports releases; act on presses only
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key);
+            }
+        }
+    }
+    Ok(())
+}
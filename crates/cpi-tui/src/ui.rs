@@ -0,0 +1,264 @@
+use crate::app::{App, Field, Outcome};
+use cpi_core::form;
+use cpi_core::grades;
+use cpi_core::semdata;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Clear, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+
+const HELP: &str = "↑/↓ Tab: move   ←/→: change   type: grade or number   Backspace: erase   Ctrl-T: grade table   Ctrl-R: reset   Esc: quit";
+
+// Width of the label column of the selection rows
+const LABEL_WIDTH: usize = 28;
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let outcome = app.evaluate();
+    let fields = app.fields();
+    let choices: Vec<Field> = fields
+        .iter()
+        .copied()
+        .filter(|f| !matches!(f, Field::Elective(_) | Field::Grade(_)))
+        .collect();
+    let [title, selection, courses, result, help] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(choices.len() as u16 + 2),
+        Constraint::Min(0),
+        Constraint::Length(4),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(Line::from("CPI / SPI Calculator").bold().centered(), title);
+    draw_selection(frame, app, &choices, selection);
+    if app.should_show_grade_input() {
+        draw_courses(frame, app, &outcome, courses);
+    }
+    draw_result(frame, &outcome, result);
+    frame.render_widget(Line::from(HELP).dim(), help);
+    if app.show_grade_table {
+        draw_grade_table(frame);
+    }
+}
+
+// The choices above the course table, one per line
+fn draw_selection(frame: &mut Frame, app: &App, choices: &[Field], area: Rect) {
+    let focused = app.focused();
+    let lines: Vec<Line> = choices
+        .iter()
+        .map(|&field| {
+            let (label, value) = describe(app, field);
+            let value = match value {
+                Some(value) => Span::raw(value),
+                None => Span::raw("Select").dim(),
+            };
+            let line = Line::from(vec![
+                Span::raw(format!("{:<width$}", label, width = LABEL_WIDTH)),
+                value,
+            ]);
+            if focused == Some(field) {
+                line.patch_style(Style::new().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title("Selection")),
+        area,
+    );
+}
+
+// Label and current value of a selection row; None until something is chosen
+fn describe(app: &App, field: Field) -> (String, Option<String>) {
+    let sem = app.sem_no.unwrap_or(0);
+    match field {
+        Field::Calculation => (
+            "Calculation".to_string(),
+            match app.calc_type {
+                Some(0) => Some("Calculate SPI".to_string()),
+                Some(1) => Some("Calculate CPI".to_string()),
+                _ => None,
+            },
+        ),
+        Field::Programme => (
+            "Programme".to_string(),
+            app.programme
+                .as_deref()
+                .and_then(semdata::get_programme)
+                .map(|p| p.name.clone()),
+        ),
+        Field::Batch => (
+            "Batch (Admission Year)".to_string(),
+            app.batch.map(|b| b.to_string()),
+        ),
+        Field::Semester => (
+            "Semester".to_string(),
+            app.sem_no.map(|s| format!("Semester {}", s)),
+        ),
+        Field::Track => (
+            format!("Track (Sem {})", sem),
+            app.track.map(|t| t.label().to_string()),
+        ),
+        Field::PathTrack(number) => (
+            format!("Track taken in Semester {}", number),
+            app.path_tracks.get(&number).map(|t| t.label().to_string()),
+        ),
+        Field::PathElective(number, index) => (
+            format!(
+                "Semester {} {}",
+                number,
+                app.path_semester(number)
+                    .map(|s| s.course_name[index].as_str())
+                    .unwrap_or_default()
+            ),
+            form::path_elective(&app.path_electives, number, index),
+        ),
+        Field::CpiOption => (
+            "CPI option".to_string(),
+            match app.calc_cpi_option {
+                Some(1) => Some(format!(
+                    "1 - previous CPI (Sem {}) and current semester grades",
+                    sem - 1
+                )),
+                Some(2) => Some(format!(
+                    "2 - previous CPI (Sem {}) and current semester SPI",
+                    sem - 1
+                )),
+                _ => None,
+            },
+        ),
+        Field::PrevCpi => (
+            format!("CPI up to Semester {}", sem - 1),
+            Some(text_value(&app.prev_cpi, "e.g., 8.5")),
+        ),
+        Field::CurrentSpi => (
+            format!("SPI of Semester {}", sem),
+            Some(text_value(&app.current_spi, "e.g., 9.0")),
+        ),
+        Field::Elective(_) | Field::Grade(_) => (String::new(), None),
+    }
+}
+
+fn text_value(value: &str, hint: &str) -> String {
+    if value.is_empty() {
+        hint.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+// One row per course, as `view_course_grade_input` lays them out on the web.
+// The focused row is kept in view; within it, the focused cell is reversed.
+fn draw_courses(frame: &mut Frame, app: &App, outcome: &Outcome, area: Rect) {
+    let (sem, curriculum) = match (app.resolved_semester(), app.curriculum()) {
+        (Some(sem), Some(curriculum)) => (sem, curriculum),
+        _ => return,
+    };
+    let focused = app.focused();
+    let focused_cell = |cell: Cell<'static>, field: Field| {
+        if focused == Some(field) {
+            cell.reversed()
+        } else {
+            cell
+        }
+    };
+    let rows = (0..sem.course_code.len()).map(|index| {
+        let elective = if curriculum.electives_for(sem.id, index).is_empty() {
+            Cell::from("")
+        } else {
+            let choice = app.electives[index]
+                .clone()
+                .unwrap_or_else(|| "Not chosen".to_string());
+            focused_cell(Cell::from(choice), Field::Elective(index))
+        };
+        let grade = match app.grades[index].as_str() {
+            "" => Cell::from("--".dim()),
+            grade => Cell::from(format!(
+                "{} ({})",
+                grade,
+                grades::default_scale()
+                    .points(grade)
+                    .map_or("?".to_string(), |p| p.to_string())
+            )),
+        };
+        let note = outcome
+            .course_errors
+            .get(&index)
+            .map_or(Cell::from(""), |err| Cell::from(err.clone().fg(Color::Red)));
+        Row::new(vec![
+            Cell::from(sem.course_code[index].clone()),
+            Cell::from(sem.course_name[index].clone()),
+            Cell::from(sem.course_credit[index].to_string()),
+            elective,
+            focused_cell(grade, Field::Grade(index)),
+            note,
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Fill(1),
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(Row::new(["Code", "Course", "Credits", "Elective", "Grade", ""]).bold())
+    .row_highlight_style(Style::new().add_modifier(Modifier::BOLD))
+    .block(Block::bordered().title(format!(
+        "Enter Grades for Semester {} (Total Credits: {})",
+        sem.id, sem.total_credit
+    )));
+
+    let mut state = TableState::default().with_selected(match focused {
+        Some(Field::Elective(index)) | Some(Field::Grade(index)) => Some(index),
+        _ => None,
+    });
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_result(frame: &mut Frame, outcome: &Outcome, area: Rect) {
+    let mut lines = Vec::new();
+    if let Some(spi) = outcome.spi {
+        lines.push(Line::from(format!("Calculated SPI: {:.3}", spi)).bold());
+    }
+    if let Some(cpi) = outcome.cpi {
+        lines.push(Line::from(format!("Calculated CPI: {:.3}", cpi)).bold());
+    }
+    if let Some(message) = &outcome.message {
+        let colour = if outcome.course_errors.is_empty() {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        lines.push(Line::from(message.clone()).fg(colour));
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title("Result")),
+        area,
+    );
+}
+
+// The grade point table, over the middle of the screen
+fn draw_grade_table(frame: &mut Frame) {
+    let scale = grades::default_scale();
+    let [area] = Layout::vertical([Constraint::Length(scale.grades().len() as u16 + 3)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::horizontal([Constraint::Length(20)])
+        .flex(Flex::Center)
+        .areas(area);
+    let rows = scale
+        .grades()
+        .iter()
+        .map(|g| Row::new([g.letter.clone(), g.points.to_string()]));
+    let table = Table::new(rows, [Constraint::Length(8), Constraint::Length(8)])
+        .header(Row::new(["Grade", "Points"]).bold())
+        .block(Block::bordered().title("Grade Point Table"));
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, area);
+}
@@ -0,0 +1,80 @@
+// SPI and CPI across semesters as an inline SVG line chart, drawn straight
+// from the transcript results so no charting library is needed.
+
+use crate::functions::SemesterResult;
+use yew::prelude::*;
+
+const WIDTH: f32 = 600.0;
+const HEIGHT: f32 = 280.0;
+const LEFT: f32 = 40.0; // Room for the grade-point labels
+const RIGHT: f32 = 20.0;
+const TOP: f32 = 20.0;
+const BOTTOM: f32 = 40.0; // Room for the semester labels
+
+// One line per measure, one point per semester. `target` adds a dashed
+// horizontal line at that CPI.
+pub fn trend_chart(results: &[SemesterResult], target: Option<f32>) -> Html {
+    if results.is_empty() {
+        return html! {};
+    }
+    let values = results.iter().flat_map(|r| [r.spi, r.cpi]).chain(target);
+    let lowest = values.fold(10.0_f32, f32::min);
+    // Start a point below the lowest value so the lines do not sit on the axis
+    let (lo, hi) = ((lowest - 1.0).floor().clamp(0.0, 9.0), 10.0);
+    let plot_width = WIDTH - LEFT - RIGHT;
+    let plot_height = HEIGHT - TOP - BOTTOM;
+    let x = |i: usize| LEFT + (i as f32 + 0.5) * plot_width / results.len() as f32;
+    let y = |v: f32| TOP + (hi - v.clamp(lo, hi)) / (hi - lo) * plot_height;
+    let line = |value: fn(&SemesterResult) -> f32| {
+        results
+            .iter()
+            .enumerate()
+            .map(|(i, r)| format!("{:.1},{:.1}", x(i), y(value(r))))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let points = |class: &'static str, name: &'static str, value: fn(&SemesterResult) -> f32| {
+        html! {
+            <g class={class}>
+                <polyline points={line(value)} />
+                { for results.iter().enumerate().map(|(i, r)| html! {
+                    <circle cx={x(i).to_string()} cy={y(value(r)).to_string()} r="4">
+                        <title>{ format!("Semester {}: {} {:.3}", r.semester, name, value(r)) }</title>
+                    </circle>
+                }) }
+            </g>
+        }
+    };
+    html! {
+        <div class="trend-chart">
+            <svg viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} role="img" aria-label="SPI and CPI per semester">
+                // Grid and axis labels, one line per grade point
+                { for (lo as u8..=hi as u8).map(|v| {
+                    let gy = y(v as f32);
+                    html! {
+                        <>
+                        <line class="grid" x1={LEFT.to_string()} x2={(WIDTH - RIGHT).to_string()} y1={gy.to_string()} y2={gy.to_string()} />
+                        <text class="axis" x={(LEFT - 8.0).to_string()} y={(gy + 4.0).to_string()} text-anchor="end">{ v }</text>
+                        </>
+                    }
+                }) }
+                { for results.iter().enumerate().map(|(i, r)| html! {
+                    <text class="axis" x={x(i).to_string()} y={(HEIGHT - BOTTOM + 20.0).to_string()} text-anchor="middle">{ format!("Sem {}", r.semester.number) }</text>
+                }) }
+                { if let Some(target) = target { html! {
+                    <g class="target">
+                        <line x1={LEFT.to_string()} x2={(WIDTH - RIGHT).to_string()} y1={y(target).to_string()} y2={y(target).to_string()} />
+                        <text x={(WIDTH - RIGHT).to_string()} y={(y(target) - 6.0).to_string()} text-anchor="end">{ format!("Target {:.2}", target) }</text>
+                    </g>
+                }} else { html!{} }}
+                { points("spi", "SPI", |r| r.spi) }
+                { points("cpi", "CPI", |r| r.cpi) }
+            </svg>
+            <div class="legend">
+                <span class="spi">{ "SPI" }</span>
+                <span class="cpi">{ "CPI" }</span>
+                { if target.is_some() { html! { <span class="target">{ "Target CPI" }</span> } } else { html!{} } }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,87 @@
+// Types for the calculation API in js_api.rs (computeSpi, computeCpi,
+// listCourses). wasm-bindgen copies this file into the declarations it
+// generates next to pkg/cpi_calculator_web.js.
+
+export type Track = "electives" | "project" | "internship";
+
+/** A semester number, or number and track for semesters offered in several (7 and 8). */
+export type SemesterRef = number | { number: number; track?: Track };
+
+export interface SpiOptions {
+  /** Admission year; defaults to the newest batch the programme has a curriculum for. */
+  batch?: number;
+  /** Elective code per course slot, in course order; null keeps the placeholder. */
+  electives?: (string | null)[];
+}
+
+export interface CpiInput extends SpiOptions {
+  /** CPI up to the previous semester; not needed for semester 1. */
+  prevCpi?: number;
+  /** SPI of the semester. Give either this or `grades`. */
+  spi?: number;
+  /** Letter grades, one per course in curriculum order. */
+  grades?: string[];
+  /** Track taken in each earlier semester that had several, e.g. `{ 7: "project" }`. */
+  path?: Record<number, Track>;
+  /** Electives chosen in earlier semesters, per semester like `electives`, e.g. `{ 7: [null, null, "CS441"] }`. */
+  pathElectives?: Record<number, (string | null)[]>;
+}
+
+export interface SpiResult {
+  programme: string;
+  batch: number;
+  semester: number;
+  track: Track | null;
+  spi: number;
+}
+
+export interface CpiResult extends SpiResult {
+  cpi: number;
+}
+
+export interface Elective {
+  code: string;
+  name: string;
+  credit: number;
+}
+
+export interface Course extends Elective {
+  /** Catalog courses that can fill this slot; empty unless it is a placeholder. */
+  electives: Elective[];
+}
+
+export interface SemesterCourses {
+  programme: string;
+  batch: number;
+  semester: number;
+  track: Track | null;
+  totalCredit: number;
+  courses: Course[];
+}
+
+export type CalcErrorKind =
+  | "invalid_input"
+  | "unknown_programme"
+  | "unknown_curriculum"
+  | "unknown_semester"
+  | "track_not_chosen"
+  | "incomplete_path"
+  | "grade_count_mismatch"
+  | "missing_grade"
+  | "invalid_grade"
+  | "invalid_elective"
+  | "zero_credits"
+  | "invalid_cpi"
+  | "invalid_spi"
+  | "out_of_order"
+  | "target_not_after_current"
+  | "not_a_backlog"
+  | "invalid_repeat_grade";
+
+/** Thrown by computeSpi, computeCpi and listCourses. */
+export interface CalcError extends Error {
+  name: "CalcError";
+  kind: CalcErrorKind;
+  /** Index (from 0) of the course at fault, for grade and elective errors. */
+  course?: number;
+}
@@ -0,0 +1,341 @@
+// The calculation engine for other pages: computeSpi, computeCpi and
+// listCourses can be called from JavaScript without mounting the App.
+// Results are plain objects; failures throw an Error named "CalcError" whose
+// `kind` (and `course`, for a course at fault) say what went wrong. The
+// TypeScript types live in js_api.d.ts.
+
+use crate::export::json_number;
+use crate::functions::{self, CalcError};
+use crate::grades;
+use crate::semdata::{self, SemesterId, Track};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = include_str!("js_api.d.ts");
+
+// Arguments and results, typed in the generated declarations by the
+// interfaces of js_api.d.ts
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SemesterRef")]
+    pub type JsSemesterRef;
+    #[wasm_bindgen(typescript_type = "SpiOptions")]
+    pub type JsSpiOptions;
+    #[wasm_bindgen(typescript_type = "CpiInput")]
+    pub type JsCpiInput;
+    #[wasm_bindgen(typescript_type = "SpiResult")]
+    pub type JsSpiResult;
+    #[wasm_bindgen(typescript_type = "CpiResult")]
+    pub type JsCpiResult;
+    #[wasm_bindgen(typescript_type = "SemesterCourses")]
+    pub type JsSemesterCourses;
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SemesterRef {
+    Number(u8),
+    Id {
+        number: u8,
+        #[serde(default)]
+        track: Option<Track>,
+    },
+}
+
+impl SemesterRef {
+    fn id(&self) -> SemesterId {
+        match *self {
+            SemesterRef::Number(number) => SemesterId::new(number),
+            SemesterRef::Id { number, track } => SemesterId { number, track },
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SpiOptions {
+    batch: Option<u16>,
+    #[serde(default)]
+    electives: Vec<Option<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CpiInput {
+    batch: Option<u16>,
+    #[serde(default)]
+    electives: Vec<Option<String>>,
+    prev_cpi: Option<f32>, // Not needed for semester 1
+    spi: Option<f32>,
+    #[serde(default)]
+    grades: Vec<String>,
+    // Keyed by semester number, e.g. {"7": "project"}
+    #[serde(default)]
+    path: BTreeMap<String, Track>,
+    #[serde(default)]
+    path_electives: BTreeMap<String, Vec<Option<String>>>,
+}
+
+#[derive(Serialize)]
+struct SpiResult<'a> {
+    programme: &'a str,
+    batch: u16,
+    semester: u8,
+    track: Option<Track>,
+    spi: f64,
+}
+
+#[derive(Serialize)]
+struct CpiResult<'a> {
+    #[serde(flatten)]
+    semester: SpiResult<'a>,
+    cpi: f64,
+}
+
+#[derive(Serialize)]
+struct Elective<'a> {
+    code: &'a str,
+    name: &'a str,
+    credit: f64,
+}
+
+#[derive(Serialize)]
+struct Course<'a> {
+    #[serde(flatten)]
+    course: Elective<'a>,
+    electives: Vec<Elective<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SemesterCourses<'a> {
+    programme: &'a str,
+    batch: u16,
+    semester: u8,
+    track: Option<Track>,
+    total_credit: f64,
+    courses: Vec<Course<'a>>,
+}
+
+/// SPI of one semester from its letter grades, one per course in curriculum order.
+#[wasm_bindgen(js_name = computeSpi)]
+pub fn compute_spi(
+    programme: &str,
+    semester: JsSemesterRef,
+    grades: Vec<String>,
+    options: Option<JsSpiOptions>,
+) -> Result<JsSpiResult, JsValue> {
+    let sem_id = semester_id(semester)?;
+    let options = match options {
+        Some(options) => from_js::<SpiOptions>(options.into(), "options")?,
+        None => SpiOptions::default(),
+    };
+    let batch = batch_for(programme, options.batch)?;
+    let spi = functions::calc_spi(
+        programme,
+        batch,
+        sem_id,
+        &options.electives,
+        grades,
+        grades::default_scale(),
+    )
+    .map_err(calc_error)?;
+    to_js(&SpiResult {
+        programme,
+        batch,
+        semester: sem_id.number,
+        track: sem_id.track,
+        spi: json_number(spi),
+    })
+}
+
+/// CPI after a semester, from the previous CPI and that semester's SPI or grades.
+#[wasm_bindgen(js_name = computeCpi)]
+pub fn compute_cpi(
+    programme: &str,
+    semester: JsSemesterRef,
+    input: JsCpiInput,
+) -> Result<JsCpiResult, JsValue> {
+    let sem_id = semester_id(semester)?;
+    let input = from_js::<CpiInput>(input.into(), "input")?;
+    let batch = batch_for(programme, input.batch)?;
+    let spi = match (input.spi, input.grades.is_empty()) {
+        (Some(spi), true) => spi,
+        (None, false) => functions::calc_spi(
+            programme,
+            batch,
+            sem_id,
+            &input.electives,
+            input.grades,
+            grades::default_scale(),
+        )
+        .map_err(calc_error)?,
+        _ => {
+            return Err(js_error(
+                "invalid_input",
+                "give either `spi` or `grades`",
+                None,
+            ))
+        }
+    };
+    // Semester 1 has no earlier CPI; its CPI is its SPI
+    let prev_cpi = match input.prev_cpi {
+        Some(prev_cpi) => prev_cpi,
+        None if sem_id.number <= 1 => 0.0,
+        None => {
+            return Err(js_error(
+                "invalid_input",
+                &format!("prevCpi is needed for semester {}", sem_id.number),
+                None,
+            ))
+        }
+    };
+    let tracks = semester_keys(input.path, "path")?;
+    let path: Vec<SemesterId> = (1..sem_id.number)
+        .map(|number| SemesterId {
+            number,
+            track: tracks.get(&number).copied(),
+        })
+        .collect();
+    let mut chosen = semester_keys(input.path_electives, "pathElectives")?;
+    let path_electives: Vec<Vec<Option<String>>> = (1..sem_id.number)
+        .map(|number| chosen.remove(&number).unwrap_or_default())
+        .collect();
+    let cpi = functions::calculate_cpi_option3(
+        programme,
+        batch,
+        sem_id,
+        &input.electives,
+        &path,
+        &path_electives,
+        prev_cpi,
+        spi,
+    )
+    .map_err(calc_error)?;
+    to_js(&CpiResult {
+        semester: SpiResult {
+            programme,
+            batch,
+            semester: sem_id.number,
+            track: sem_id.track,
+            spi: json_number(spi),
+        },
+        cpi: json_number(cpi),
+    })
+}
+
+/// Courses of a semester, with the electives that can fill each placeholder slot.
+#[wasm_bindgen(js_name = listCourses)]
+pub fn list_courses(
+    programme: &str,
+    semester: JsSemesterRef,
+    batch: Option<u16>,
+) -> Result<JsSemesterCourses, JsValue> {
+    let sem_id = semester_id(semester)?;
+    let batch = batch_for(programme, batch)?;
+    let curriculum = semdata::get_curriculum(programme, batch).ok_or_else(|| {
+        calc_error(CalcError::UnknownCurriculum {
+            programme: programme.to_string(),
+            batch,
+        })
+    })?;
+    if sem_id.track.is_none() && !curriculum.tracks(sem_id.number).is_empty() {
+        return Err(calc_error(CalcError::TrackNotChosen(sem_id.number)));
+    }
+    let sem = curriculum
+        .semester(sem_id)
+        .ok_or_else(|| calc_error(CalcError::UnknownSemester(sem_id)))?;
+    let courses = (0..sem.course_code.len())
+        .map(|index| Course {
+            course: Elective {
+                code: &sem.course_code[index],
+                name: &sem.course_name[index],
+                credit: json_number(sem.course_credit[index]),
+            },
+            electives: curriculum
+                .electives_for(sem_id, index)
+                .into_iter()
+                .map(|e| Elective {
+                    code: &e.code,
+                    name: &e.name,
+                    credit: json_number(e.credit),
+                })
+                .collect(),
+        })
+        .collect();
+    to_js(&SemesterCourses {
+        programme,
+        batch,
+        semester: sem_id.number,
+        track: sem_id.track,
+        total_credit: json_number(sem.total_credit),
+        courses,
+    })
+}
+
+// `batch`, or the newest batch the programme has a curriculum for. The year
+// comes from the browser, as wasm has no system clock to read.
+fn batch_for(programme: &str, batch: Option<u16>) -> Result<u16, JsValue> {
+    functions::batch_for(
+        programme,
+        batch,
+        js_sys::Date::new_0().get_full_year() as u16,
+    )
+    .map_err(calc_error)
+}
+
+fn semester_id(semester: JsSemesterRef) -> Result<SemesterId, JsValue> {
+    serde_wasm_bindgen::from_value::<SemesterRef>(semester.into())
+        .map(|semester| semester.id())
+        .map_err(|_| {
+            js_error(
+                "invalid_input",
+                "semester must be a number or { number, track }",
+                None,
+            )
+        })
+}
+
+// Object keyed by semester number; JS object keys arrive as strings
+fn semester_keys<T>(entries: BTreeMap<String, T>, what: &str) -> Result<BTreeMap<u8, T>, JsValue> {
+    entries
+        .into_iter()
+        .map(|(number, value)| match number.parse::<u8>() {
+            Ok(number) => Ok((number, value)),
+            Err(_) => Err(js_error(
+                "invalid_input",
+                &format!("invalid {}: '{}' is not a semester number", what, number),
+                None,
+            )),
+        })
+        .collect()
+}
+
+fn from_js<T: for<'de> Deserialize<'de>>(value: JsValue, what: &str) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| js_error("invalid_input", &format!("invalid {}: {}", what, e), None))
+}
+
+// Plain objects, with null rather than undefined for a missing track
+fn to_js<T: Serialize, R: JsCast>(value: &T) -> Result<R, JsValue> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map(JsCast::unchecked_into)
+        .map_err(|e| js_error("invalid_input", &e.to_string(), None))
+}
+
+fn calc_error(err: CalcError) -> JsValue {
+    js_error(err.kind(), &err.to_string(), err.course())
+}
+
+fn js_error(kind: &str, message: &str, course: Option<usize>) -> JsValue {
+    let error = js_sys::Error::new(message);
+    error.set_name("CalcError");
+    let _ = js_sys::Reflect::set(&error, &"kind".into(), &kind.into());
+    if let Some(course) = course {
+        let _ = js_sys::Reflect::set(&error, &"course".into(), &(course as u32).into());
+    }
+    error.into()
+}
@@ -1,16 +1,36 @@
-mod functions;
-mod semdata;
+mod chart;
+pub mod js_api;
+pub mod storage;
+
+// The calculation core, re-exported so paths like `crate::functions` keep working
+pub use cpi_core::{export, form, functions, grades, import, semdata, share};
 
 use wasm_bindgen::prelude::*;
 use yew::prelude::*;
 use web_sys::HtmlInputElement;
+use form::{FormError, SemesterForm};
+use functions::{CalcError, CourseRef, RepeatPolicy};
+use semdata::{SemesterId, Track};
+use std::collections::{BTreeMap, HashMap};
+
+#[wasm_bindgen]
+extern "C" {
+    // navigator.clipboard.writeText; throws where the Clipboard API is unavailable
+    #[wasm_bindgen(catch, js_namespace = ["navigator", "clipboard"], js_name = writeText)]
+    fn clipboard_write_text(text: &str) -> Result<js_sys::Promise, JsValue>;
+}
 
 #[derive(Debug)]
 pub enum Msg {
     SelectCalcType(String),
-    SelectSemester(String),
-    SelectSemOption(String),
+    SelectProgramme(String),
+    SelectBatch(String),
+    SelectSemester(Option<u8>),
+    SelectTrack(Option<Track>),
+    SelectPathTrack(u8, Option<Track>), // (earlier semester number, track taken in it)
+    SelectPathElective(u8, usize, Option<String>), // (earlier semester number, course index, elective code)
     UpdateGrade(usize, String),
+    SelectElective(usize, Option<String>), // (course index, elective code)
     UpdateCpiOp1Var1(String),
     UpdateCpiOp2Var1(String),
     UpdateCpiOp2Var2(String),
@@ -19,16 +39,68 @@ pub enum Msg {
     ToggleGradeTable,
     SetDone,
     SelectCpiCalcOption(String),
+    AddTranscriptSemester,
+    RemoveTranscriptSemester,
+    SelectTranscriptTrack(usize, Option<Track>),
+    UpdateTranscriptGrade(usize, usize, String), // (semester index, course index, grade)
+    SelectTranscriptElective(usize, usize, Option<String>), // (semester index, course index, elective code)
+    SelectRepeatPolicy(String),
+    AddRepeat(usize), // Semester index
+    RemoveRepeat(usize, usize), // (semester index, repeat index)
+    SelectRepeatCourse(usize, usize, String), // (semester index, repeat index, "semester-course")
+    UpdateRepeatGrade(usize, usize, String), // (semester index, repeat index, grade)
+    SelectTargetFromSemester(Option<u8>),
+    SelectTargetFromTrack(Option<Track>),
+    SelectTargetToSemester(Option<u8>),
+    SelectTargetToTrack(Option<Track>),
+    UpdateTargetCurrentCpi(String),
+    UpdateTargetCpi(String),
+    UpdateWhatIfPrevCpi(String),
+    CopyLink,
+    Download(export::ExportFormat),
+    UpdateImportText(String),
+    ImportFile(web_sys::File),
+    ImportFileLoaded(Option<String>), // Text of the chosen file; None if it could not be read
+    ImportGrades,
+    ShowReport(bool),
+    UpdateStudentName(String),
+    UpdateRollNumber(String),
+    PrintReport,
+    UpdateChartTarget(String),
+}
+
+// One semester row of the transcript editor; its number is its position + 1.
+struct TranscriptRow {
+    track: Option<Track>,
+    grades: Vec<String>,
+    electives: Vec<Option<String>>, // Chosen elective per course slot
+    repeats: Vec<functions::Repeat>, // Backlogs from earlier semesters taken again in this one
+}
+
+// Inputs of the target CPI planner: the CPI after the current ("from")
+// semester and the CPI wanted after a later ("to") semester.
+#[derive(Default)]
+struct TargetInputs {
+    from_sem: Option<u8>,
+    from_track: Option<Track>,
+    current_cpi_str: String,
+    to_sem: Option<u8>,
+    to_track: Option<Track>,
+    target_cpi_str: String,
 }
 
 pub struct App {
-    calc_type: Option<i32>, // 0 for SPI, 1 for CPI
-    sem_no: Option<i32>,
-    sem_option: Option<i32>, // For sem 7/8
-    sem_no_f32: Option<f32>, // Combined sem_no and option
+    calc_type: Option<i32>, // 0 for SPI, 1 for CPI, 2 for full transcript, 3 for target CPI, 4 for what-if
+    programme: Option<String>, // Programme id, e.g. "cse"
+    batch: Option<u16>, // Admission year, picks the curriculum version
+    sem_no: Option<u8>,
+    track: Option<Track>, // For semesters offered in several tracks (7/8)
+    path_tracks: BTreeMap<u8, Track>, // Tracks taken in earlier semesters, for the credits CPI is weighted by
+    path_electives: BTreeMap<u8, Vec<Option<String>>>, // Electives taken in earlier semesters, by course index
     done_1: bool, // Tracks if initial semester selection is done
     sem_info: Option<semdata::Semester>,
     grades: Vec<String>, // Store grades as strings for input binding
+    electives: Vec<Option<String>>, // Elective bound to each placeholder slot, parallel to grades
     calculated_spi: Option<f32>,
     calculated_cpi: Option<f32>,
     show_grade_table: bool,
@@ -37,6 +109,22 @@ pub struct App {
     cpi_op2_var1_str: String,
     cpi_op2_var2_str: String,
     error_message: Option<String>,
+    course_errors: HashMap<String, String>, // Per-row grade messages, keyed by the grade select's id
+    transcript: Vec<TranscriptRow>,
+    transcript_results: Option<Vec<functions::SemesterResult>>,
+    repeat_policy: RepeatPolicy,
+    target: TargetInputs,
+    target_plan: Option<functions::TargetPlan>,
+    what_if_prev_cpi_str: String,
+    simulation: Option<functions::Simulation>,
+    storage: Box<dyn storage::Storage>, // Keeps inputs across page reloads
+    share_link: Option<String>, // Last link produced by 'Copy Link'
+    import_text: String, // Pasted portal text, or the contents of a chosen CSV/JSON file
+    import_notice: Option<String>, // What the last import filled in and what it could not place
+    show_report: bool, // The printable report replaces the calculator while set
+    student_name: String, // For the report heading only
+    roll_number: String,
+    chart_target_str: String, // Target CPI drawn across the transcript trend chart
 }
 
 impl Component for App {
@@ -44,14 +132,29 @@ impl Component for App {
     type Properties = ();
 
     fn create(_ctx: &Context<Self>) -> Self {
-        Self {
+        // Fall back to memory if localStorage is unavailable (e.g. blocked by the browser)
+        let storage: Box<dyn storage::Storage> = match storage::LocalStorage::open() {
+            Some(local) => Box::new(local),
+            None => Box::new(storage::MemoryStorage::default()),
+        };
+        let saved = storage::load_state(storage.as_ref());
+        // A calculation shared through the URL fragment wins over the saved state
+        let shared = web_sys::window()
+            .and_then(|w| w.location().hash().ok())
+            .filter(|hash| hash.len() > 1)
+            .and_then(|hash| share::decode(&hash).ok());
+        let mut app = Self {
             calc_type: None,
+            programme: None,
+            batch: None,
             sem_no: None,
-            sem_option: None,
-            sem_no_f32: None,
+            track: None,
+            path_tracks: BTreeMap::new(),
+            path_electives: BTreeMap::new(),
             done_1: false,
             sem_info: None,
             grades: Vec::new(),
+            electives: Vec::new(),
             calculated_spi: None,
             calculated_cpi: None,
             show_grade_table: false,
@@ -60,19 +163,66 @@ impl Component for App {
             cpi_op2_var1_str: "".to_string(),
             cpi_op2_var2_str: "".to_string(),
             error_message: None,
+            course_errors: HashMap::new(),
+            transcript: Vec::new(),
+            transcript_results: None,
+            repeat_policy: RepeatPolicy::default(),
+            target: TargetInputs::default(),
+            target_plan: None,
+            what_if_prev_cpi_str: "".to_string(),
+            simulation: None,
+            storage,
+            share_link: None,
+            import_text: "".to_string(),
+            import_notice: None,
+            show_report: false,
+            student_name: "".to_string(),
+            roll_number: "".to_string(),
+            chart_target_str: "".to_string(),
+        };
+        if let Some(shared) = shared {
+            app.restore(Self::saved_from_shared(shared));
+            // Once restored it is the user's own calculation; a later reload
+            // should bring back their edits, not the link again
+            clear_location_hash();
+        } else if let Some(saved) = saved {
+            app.restore(saved);
         }
+        app
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        // Clear previous results and errors on most interactions (except Reset and ToggleGradeTable)
-        if !matches!(msg, Msg::Reset | Msg::ToggleGradeTable) {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let is_reset = matches!(msg, Msg::Reset);
+        // Clear previous results and errors on most interactions (except Reset, which clears everything itself,
+        // ToggleGradeTable, CopyLink, Download, the report's own and the chart target)
+        let keeps_results = matches!(
+            msg,
+            Msg::Reset
+                | Msg::ToggleGradeTable
+                | Msg::CopyLink
+                | Msg::Download(_)
+                | Msg::ShowReport(_)
+                | Msg::UpdateStudentName(_)
+                | Msg::UpdateRollNumber(_)
+                | Msg::PrintReport
+                | Msg::UpdateChartTarget(_)
+        );
+        if !keeps_results {
              self.calculated_spi = None;
              self.calculated_cpi = None;
+             self.transcript_results = None;
+             self.target_plan = None;
+             self.simulation = None;
              self.error_message = None;
+             self.course_errors.clear();
+             if self.share_link.take().is_some() {
+                 clear_location_hash(); // The link no longer matches the inputs
+             }
+             self.import_notice = None;
         }
 
 
-        match msg {
+        let render = match msg {
             Msg::SelectCalcType(val) => {
                 self.calc_type = val.parse().ok();
                 // Reset dependent state
@@ -80,34 +230,63 @@ impl Component for App {
                 self.done_1 = false; // Need to re-confirm semester
                 true // Re-render needed
             }
-            Msg::SelectSemester(val) => {
-                self.sem_no = val.parse().ok();
-                // Reset options if semester changes
-                self.sem_option = None;
-                self.sem_no_f32 = self.sem_no.map(|n| n as f32);
+            Msg::SelectProgramme(val) => {
+                let programme = semdata::get_programme(&val);
+                self.programme = programme.map(|p| p.id.clone());
+                // Keep the batch only if the new programme has a curriculum for it
+                if !matches!((programme, self.batch), (Some(p), Some(b)) if p.curriculum(b).is_some()) {
+                    self.batch = None;
+                }
+                self.path_electives.clear();
                 self.done_1 = false; // Need to re-confirm semester
                 self.reset_semester_dependent_state();
                 true
             }
-            Msg::SelectSemOption(val) => {
-                self.sem_option = val.parse().ok();
-                if let (Some(sem), Some(opt)) = (self.sem_no, self.sem_option) {
-                     if sem == 7 || sem == 8 {
-                        self.sem_no_f32 = Some(sem as f32 + 0.1 * opt as f32);
-                     } else {
-                         self.sem_no_f32 = Some(sem as f32); // Fallback if option selected for wrong sem
-                     }
-                }
+            Msg::SelectBatch(val) => {
+                self.batch = val.parse().ok();
+                self.path_electives.clear();
+                self.done_1 = false; // Need to re-confirm semester
+                self.reset_semester_dependent_state();
+                true
+            }
+            Msg::SelectSemester(sem_no) => {
+                self.sem_no = sem_no;
+                // Reset track if semester changes
+                self.track = None;
+                self.done_1 = false; // Need to re-confirm semester
+                self.reset_semester_dependent_state();
+                true
+            }
+            Msg::SelectTrack(track) => {
+                self.track = track;
                 self.done_1 = false; // Need to re-confirm semester
                 self.reset_semester_dependent_state();
                 true
+            }
+            Msg::SelectPathTrack(number, track) => {
+                match track {
+                    Some(track) => self.path_tracks.insert(number, track),
+                    None => self.path_tracks.remove(&number),
+                };
+                // The other track has other courses
+                self.path_electives.remove(&number);
+                true
+            }
+            Msg::SelectPathElective(number, index, code) => {
+                form::choose_path_elective(&mut self.path_electives, number, index, code);
+                true
             }
              Msg::SetDone => {
-                if self.sem_no.is_some() {
-                    // Check if options are needed and selected
-                    let sem = self.sem_no.unwrap();
-                    if (sem == 7 || sem == 8) && self.sem_option.is_none() {
-                         self.error_message = Some("Please select an option for Semester 7/8.".to_string());
+                if self.programme.is_none() {
+                    self.error_message = Some("Please select a programme first.".to_string());
+                    self.done_1 = false;
+                } else if self.batch.is_none() {
+                    self.error_message = Some("Please select your batch first.".to_string());
+                    self.done_1 = false;
+                } else if let Some(sem) = self.sem_no {
+                    // Check if a track is needed and selected
+                    if !self.available_tracks(sem).is_empty() && self.track.is_none() {
+                         self.error_message = Some(format!("Please select a track for Semester {}.", sem));
                          self.done_1 = false;
                     } else {
                         self.done_1 = true;
@@ -122,18 +301,24 @@ impl Component for App {
             Msg::UpdateGrade(index, grade_str) => {
                 if let Some(_sem_info) = &self.sem_info { // Use _sem_info if not needed inside
                     if index < self.grades.len() {
-                         // Basic validation: Allow empty or numbers between 0 and 10
-                        if grade_str.is_empty() || (grade_str.parse::<f32>().map_or(false, |g| g >= 0.0 && g <= 10.0)) {
+                         // Basic validation: Allow empty or a letter grade from the scale
+                        if grade_str.is_empty() || grades::default_scale().points(&grade_str).is_some() {
                             self.grades[index] = grade_str;
                             self.error_message = None; // Clear error on valid input
                         } else {
                              // Provide specific feedback but don't overwrite the input value immediately
-                             self.error_message = Some(format!("Invalid grade '{}'. Please enter a number between 0 and 10.", grade_str));
+                             self.error_message = Some(format!("Invalid grade '{}'. Please pick a letter grade.", grade_str));
                         }
                     }
                 }
                 true // Re-render to show updated value or error
             }
+            Msg::SelectElective(index, code) => {
+                if let Some(slot) = self.electives.get_mut(index) {
+                    *slot = code;
+                }
+                true
+            }
             Msg::SelectCpiCalcOption(val) => {
                 self.calc_cpi_option = val.parse().ok();
                 // Reset CPI specific inputs when option changes
@@ -165,12 +350,16 @@ impl Component for App {
             Msg::Reset => {
                  // Reset state fields directly to defaults
                 self.calc_type = None;
+                self.programme = None;
+                self.batch = None;
                 self.sem_no = None;
-                self.sem_option = None;
-                self.sem_no_f32 = None;
+                self.track = None;
+                self.path_tracks.clear();
+                self.path_electives.clear();
                 self.done_1 = false;
                 self.sem_info = None;
                 self.grades = Vec::new();
+                self.electives = Vec::new();
                 self.calculated_spi = None;
                 self.calculated_cpi = None;
                 self.show_grade_table = false;
@@ -179,17 +368,229 @@ impl Component for App {
                 self.cpi_op2_var1_str = "".to_string();
                 self.cpi_op2_var2_str = "".to_string();
                 self.error_message = None;
+                self.course_errors.clear();
+                self.transcript = Vec::new();
+                self.transcript_results = None;
+                self.repeat_policy = RepeatPolicy::default();
+                self.target = TargetInputs::default();
+                self.target_plan = None;
+                self.what_if_prev_cpi_str = "".to_string();
+                self.simulation = None;
+                self.import_text = "".to_string();
+                self.import_notice = None;
+                self.chart_target_str = "".to_string();
+                self.show_report = false;
+                self.student_name = "".to_string();
+                self.roll_number = "".to_string();
+                self.share_link = None;
+                clear_location_hash();
+                storage::clear_state(self.storage.as_ref());
                 true // Re-render needed
             }
             Msg::ToggleGradeTable => {
                 self.show_grade_table = !self.show_grade_table;
                 true
             }
+            Msg::AddTranscriptSemester => {
+                self.add_transcript_semester();
+                true
+            }
+            Msg::RemoveTranscriptSemester => {
+                self.transcript.pop();
+                true
+            }
+            Msg::SelectTranscriptTrack(index, track) => {
+                let course_count = self
+                    .curriculum()
+                    .and_then(|c| c.semester(SemesterId { number: index as u8 + 1, track }))
+                    .map_or(0, |sem| sem.course_code.len());
+                if let Some(row) = self.transcript.get_mut(index) {
+                    row.track = track;
+                    row.grades = vec!["".to_string(); course_count];
+                    row.electives = vec![None; course_count];
+                }
+                true
+            }
+            Msg::UpdateTranscriptGrade(index, course, grade_str) => {
+                if let Some(slot) = self.transcript.get_mut(index).and_then(|row| row.grades.get_mut(course)) {
+                    if grade_str.is_empty() || grades::default_scale().points(&grade_str).is_some() {
+                        *slot = grade_str;
+                    } else {
+                        self.error_message = Some(format!("Invalid grade '{}'. Please pick a letter grade.", grade_str));
+                    }
+                }
+                true
+            }
+            Msg::SelectTranscriptElective(index, course, code) => {
+                if let Some(slot) = self.transcript.get_mut(index).and_then(|row| row.electives.get_mut(course)) {
+                    *slot = code;
+                }
+                true
+            }
+            Msg::SelectRepeatPolicy(val) => {
+                self.repeat_policy = val.parse().unwrap_or_default();
+                true
+            }
+            Msg::AddRepeat(index) => {
+                let taken: Vec<CourseRef> = self.transcript.get(index).map(|row| row.repeats.iter().map(|r| r.course).collect()).unwrap_or_default();
+                let next = self.backlogs_before(index).into_iter().map(|b| b.course).find(|course| !taken.contains(course));
+                if let (Some(row), Some(course)) = (self.transcript.get_mut(index), next) {
+                    row.repeats.push(functions::Repeat { course, grade: "".to_string() });
+                }
+                true
+            }
+            Msg::RemoveRepeat(index, repeat) => {
+                if let Some(row) = self.transcript.get_mut(index) {
+                    if repeat < row.repeats.len() {
+                        row.repeats.remove(repeat);
+                    }
+                }
+                true
+            }
+            Msg::SelectRepeatCourse(index, repeat, val) => {
+                let course = val.split_once('-').and_then(|(sem, course)| Some(CourseRef { semester: sem.parse().ok()?, course: course.parse().ok()? }));
+                if let (Some(slot), Some(course)) = (self.transcript.get_mut(index).and_then(|row| row.repeats.get_mut(repeat)), course) {
+                    slot.course = course;
+                }
+                true
+            }
+            Msg::UpdateRepeatGrade(index, repeat, grade_str) => {
+                if let Some(slot) = self.transcript.get_mut(index).and_then(|row| row.repeats.get_mut(repeat)) {
+                    if grade_str.is_empty() || grades::default_scale().points(&grade_str).is_some() {
+                        slot.grade = grade_str;
+                    } else {
+                        self.error_message = Some(format!("Invalid grade '{}'. Please pick a letter grade.", grade_str));
+                    }
+                }
+                true
+            }
+            Msg::SelectTargetFromSemester(sem_no) => {
+                self.target.from_sem = sem_no;
+                self.target.from_track = None;
+                true
+            }
+            Msg::SelectTargetFromTrack(track) => {
+                self.target.from_track = track;
+                if let Some(number) = self.target.from_sem {
+                    self.path_electives.remove(&number);
+                }
+                true
+            }
+            Msg::SelectTargetToSemester(sem_no) => {
+                self.target.to_sem = sem_no;
+                self.target.to_track = None;
+                true
+            }
+            Msg::SelectTargetToTrack(track) => {
+                self.target.to_track = track;
+                if let Some(number) = self.target.to_sem {
+                    self.path_electives.remove(&number);
+                }
+                true
+            }
+            Msg::UpdateTargetCurrentCpi(val) => {
+                self.target.current_cpi_str = val;
+                true
+            }
+            Msg::UpdateTargetCpi(val) => {
+                self.target.target_cpi_str = val;
+                true
+            }
+            Msg::UpdateWhatIfPrevCpi(val) => {
+                self.what_if_prev_cpi_str = val;
+                true
+            }
+            Msg::CopyLink => {
+                if let Some(location) = web_sys::window().map(|w| w.location()) {
+                    let fragment = share::encode(&self.shared_state());
+                    let _ = location.set_hash(&fragment);
+                    if let Ok(href) = location.href() {
+                        // Copying may be refused (e.g. outside HTTPS); the link is shown either way
+                        let _ = clipboard_write_text(&href);
+                        self.share_link = Some(href);
+                    }
+                }
+                true
+            }
+            Msg::Download(format) => match self.export() {
+                Some(export) => {
+                    if download_file(&export.file_name(format), &format.render(&export), format.media_type()).is_err() {
+                        self.error_message = Some("Could not start the download.".to_string());
+                        return true;
+                    }
+                    false
+                }
+                None => false,
+            },
+            Msg::UpdateImportText(text) => {
+                self.import_text = text;
+                true
+            }
+            Msg::ImportFile(file) => {
+                ctx.link().send_future(async move {
+                    let text = wasm_bindgen_futures::JsFuture::from(file.text()).await;
+                    Msg::ImportFileLoaded(text.ok().and_then(|t| t.as_string()))
+                });
+                false
+            }
+            Msg::ImportFileLoaded(Some(text)) => {
+                self.import_text = text;
+                self.import_grades();
+                true
+            }
+            Msg::ImportFileLoaded(None) => {
+                self.error_message = Some("Could not read the file.".to_string());
+                true
+            }
+            Msg::ImportGrades => {
+                self.import_grades();
+                true
+            }
+            Msg::ShowReport(show) => {
+                self.show_report = show;
+                true
+            }
+            Msg::UpdateStudentName(name) => {
+                self.student_name = name;
+                true
+            }
+            Msg::UpdateRollNumber(roll) => {
+                self.roll_number = roll;
+                true
+            }
+            Msg::PrintReport => {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.print();
+                }
+                false
+            }
+            Msg::UpdateChartTarget(val) => {
+                self.chart_target_str = val;
+                true
+            }
+        };
+
+        // The what-if simulator recalculates on every input instead of waiting for Calculate
+        if self.calc_type == Some(4) && self.done_1 {
+            self.run_simulation();
         }
+        // The report view needs results; without them go back to the calculator
+        if self.show_report && self.export().is_none() {
+            self.show_report = false;
+        }
+        if !is_reset {
+            storage::save_state(self.storage.as_ref(), &self.snapshot());
+        }
+        render
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let link = ctx.link();
+        if self.show_report {
+            if let Some(export) = self.export() {
+                return self.view_report(link, &export);
+            }
+        }
 
         html! {
             // Added wrapper div with ID for CSS styling
@@ -204,51 +605,39 @@ impl Component for App {
                         <option value="" selected={self.calc_type.is_none()} disabled=true>{ "Select" }</option>
                         <option value="0" selected={self.calc_type == Some(0)}>{ "Calculate SPI" }</option>
                         <option value="1" selected={self.calc_type == Some(1)}>{ "Calculate CPI" }</option>
+                        <option value="2" selected={self.calc_type == Some(2)}>{ "Full Transcript (SPI and CPI for every semester)" }</option>
+                        <option value="3" selected={self.calc_type == Some(3)}>{ "Target CPI Planner (SPI needed to reach a CPI)" }</option>
+                        <option value="4" selected={self.calc_type == Some(4)}>{ "What-If Simulator (live SPI/CPI per course)" }</option>
                     </select>
                 </div>
 
-                // --- Semester Selection (Common for SPI/CPI) ---
+                // --- Programme and Semester Selection (Common for SPI/CPI) ---
                 { if self.calc_type.is_some() { html! {
                     <div>
-                        <label for="semester">{ "Select Semester:" }</label>
-                        <select id="semester" onchange={link.callback(|e: Event| Msg::SelectSemester(e.target_unchecked_into::<HtmlInputElement>().value()))}>
-                            <option value="" selected={self.sem_no.is_none()} disabled=true>{ "Select" }</option>
-                            { for (1..=8).map(|i| html! { <option value={i.to_string()} selected={self.sem_no == Some(i)}>{ format!("Semester {}", i) }</option> }) }
+                        <label for="programme">{ "Select Programme:" }</label>
+                        <select id="programme" onchange={link.callback(|e: Event| Msg::SelectProgramme(e.target_unchecked_into::<HtmlInputElement>().value()))}>
+                            <option value="" selected={self.programme.is_none()} disabled=true>{ "Select" }</option>
+                            { for semdata::programmes().iter().map(|p| html! { <option value={p.id.clone()} selected={self.programme.as_deref() == Some(p.id.as_str())}>{ &p.name }</option> }) }
                         </select>
 
-                        // --- Semester Options (for Sem 7/8) ---
-                        { if let Some(sem) = self.sem_no {
-                            if sem == 7 { html! {
-                                <>
-                                <label for="sem_option">{ "Select Option (Sem 7):" }</label>
-                                <select id="sem_option" onchange={link.callback(|e: Event| Msg::SelectSemOption(e.target_unchecked_into::<HtmlInputElement>().value()))}>
-                                    <option value="" selected={self.sem_option.is_none()} disabled=true>{ "Select" }</option>
-                                    <option value="1" selected={self.sem_option == Some(1)}>{ "Option 1" }</option>
-                                    <option value="2" selected={self.sem_option == Some(2)}>{ "Option 2" }</option>
-                                </select>
-                                </>
-                            }} else if sem == 8 { html! {
-                                <>
-                                <label for="sem_option">{ "Select Option (Sem 8):" }</label>
-                                <select id="sem_option" onchange={link.callback(|e: Event| Msg::SelectSemOption(e.target_unchecked_into::<HtmlInputElement>().value()))}>
-                                    <option value="" selected={self.sem_option.is_none()} disabled=true>{ "Select" }</option>
-                                    <option value="1" selected={self.sem_option == Some(1)}>{ "Option 1" }</option>
-                                    <option value="2" selected={self.sem_option == Some(2)}>{ "Option 2" }</option>
-                                    <option value="3" selected={self.sem_option == Some(3)}>{ "Option 3" }</option>
-                                </select>
-                                </>
-                            }} else { html!{} } // No options for other semesters
-                        } else { html!{} }}
-
-                        // --- Done Button ---
-                        <button onclick={link.callback(|_| Msg::SetDone)}>{ "Done" }</button>
-
-                        // --- Display Selected Semester/Option ---
-                        { if let Some(sem_f32) = self.sem_no_f32 {
-                             if self.done_1 { // Only show after 'Done' is clicked
-                                html!{ <p>{ format!("Selected: Semester {:.1}", sem_f32) }</p> }
-                             } else { html!{} }
-                        } else { html!{} }}
+                        { self.view_batch_select(link) }
+
+                        // The transcript editor and the planner pick their own semesters
+                        { if !matches!(self.calc_type, Some(2) | Some(3)) { html! {
+                            <>
+                            { self.view_semester_select(link) }
+
+                            // --- Done Button ---
+                            <button onclick={link.callback(|_| Msg::SetDone)}>{ "Done" }</button>
+
+                            // --- Display Selected Semester/Option ---
+                            { if let (Some(programme), Some(batch), Some(sem_id)) = (self.programme.as_deref().and_then(semdata::get_programme), self.batch, self.semester_id()) {
+                                 if self.done_1 { // Only show after 'Done' is clicked
+                                    html!{ <p>{ format!("Selected: {} ({} batch), Semester {}", programme.name, batch, sem_id) }</p> }
+                                 } else { html!{} }
+                            } else { html!{} }}
+                            </>
+                        }} else { html!{} }}
                     </div>
                 }} else { html!{} }} // End of calc_type check
 
@@ -259,20 +648,44 @@ impl Component for App {
                     self.view_grade_input(link)
                 } else { html!{} }}
 
+                // --- Transcript Editor ---
+                { if self.calc_type == Some(2) {
+                    self.view_transcript_editor(link)
+                } else { html!{} }}
+
+                // --- Target CPI Planner ---
+                { if self.calc_type == Some(3) {
+                    self.view_target_planner(link)
+                } else { html!{} }}
+
                 // --- CPI Calculation Options (Only for CPI and Sem > 1) ---
-                 { if self.done_1 && self.calc_type == Some(1) && self.sem_no.map_or(false, |s| s > 1) {
+                 { if self.done_1 && self.calc_type == Some(1) && self.sem_no.is_some_and(|s| s > 1) {
                     self.view_cpi_options(link)
                  } else { html!{} }}
 
 
                 // --- Calculate Button ---
-                 { if self.done_1 && (self.should_show_grade_input() || self.calc_cpi_option == Some(2)) { html! {
+                 { if (self.done_1 && self.calc_type != Some(4) && (self.should_show_grade_input() || self.calc_cpi_option == Some(2)))
+                     || (self.calc_type == Some(2) && !self.transcript.is_empty())
+                     || (self.calc_type == Some(3) && self.curriculum().is_some()) { html! {
                     <button onclick={link.callback(|_| Msg::Calculate)}>{ "Calculate" }</button>
                  }} else { html!{} }}
 
 
                 // --- Display Results ---
                 { self.view_results() }
+                { self.view_transcript_results(link) }
+                { self.view_target_plan() }
+                { self.view_simulation(link) }
+
+                // --- Download Results ---
+                { if self.export().is_some() { html! {
+                    <div class="download-buttons">
+                        <button onclick={link.callback(|_| Msg::Download(export::ExportFormat::Csv))}>{ "Download CSV" }</button>
+                        <button onclick={link.callback(|_| Msg::Download(export::ExportFormat::Json))}>{ "Download JSON" }</button>
+                        <button onclick={link.callback(|_| Msg::ShowReport(true))}>{ "Printable Report" }</button>
+                    </div>
+                }} else { html!{} }}
 
                 // --- Display Errors ---
                 { self.view_error() }
@@ -284,9 +697,18 @@ impl Component for App {
                     <button onclick={link.callback(|_| Msg::ToggleGradeTable)}>
                         { if self.show_grade_table { "Hide Grade Table" } else { "Show Grade Table" } }
                     </button>
+                    <button onclick={link.callback(|_| Msg::CopyLink)}>{ "Copy Link" }</button>
                     <button onclick={link.callback(|_| Msg::Reset)} class="reset-button">{ "Reset" }</button> // Added class for potential specific styling
                 </div>
 
+                // --- Shareable Link ---
+                { if let Some(href) = &self.share_link { html! {
+                    <div class="share-link">
+                        <label for="share_link">{ "Link copied. Anyone opening it sees this calculation:" }</label>
+                        <input id="share_link" type="text" readonly=true value={href.clone()} />
+                    </div>
+                }} else { html!{} }}
+
                 // --- Grade Table ---
                 { self.view_grade_table() }
 
@@ -296,10 +718,167 @@ impl Component for App {
 }
 
 impl App {
+    // Inputs worth keeping across reloads
+    fn snapshot(&self) -> storage::SavedState {
+        let prev_cpi = match (self.calc_type, self.calc_cpi_option) {
+            (Some(4), _) => &self.what_if_prev_cpi_str,
+            (_, Some(2)) => &self.cpi_op2_var1_str,
+            _ => &self.cpi_op1_var1_str,
+        };
+        storage::SavedState {
+            calc_type: self.calc_type,
+            programme: self.programme.clone(),
+            batch: self.batch,
+            sem_no: self.sem_no,
+            track: self.track,
+            calc_cpi_option: self.calc_cpi_option,
+            grades: self.grades.clone(),
+            electives: self.electives.clone(),
+            prev_cpi: prev_cpi.clone(),
+            current_spi: self.cpi_op2_var2_str.clone(),
+            transcript: self
+                .transcript
+                .iter()
+                .map(|row| storage::SavedTranscriptRow {
+                    track: row.track,
+                    grades: row.grades.clone(),
+                    electives: row.electives.clone(),
+                    repeats: row.repeats.clone(),
+                })
+                .collect(),
+            repeat_policy: self.repeat_policy,
+            path_tracks: self.path_tracks.clone(),
+            path_electives: self.path_electives.clone(),
+            target: storage::SavedTarget {
+                from_sem: self.target.from_sem,
+                from_track: self.target.from_track,
+                current_cpi: self.target.current_cpi_str.clone(),
+                to_sem: self.target.to_sem,
+                to_track: self.target.to_track,
+                target_cpi: self.target.target_cpi_str.clone(),
+            },
+            ..Default::default()
+        }
+    }
+
+    // The current calculation in shareable form
+    fn shared_state(&self) -> share::SharedState {
+        let saved = self.snapshot();
+        share::SharedState {
+            calc_type: saved.calc_type,
+            programme: saved.programme,
+            batch: saved.batch,
+            sem_no: saved.sem_no,
+            track: saved.track,
+            cpi_option: saved.calc_cpi_option,
+            grades: saved.grades,
+            electives: saved.electives,
+            prev_cpi: Some(saved.prev_cpi),
+            current_spi: Some(saved.current_spi),
+            path_tracks: saved.path_tracks,
+            path_electives: saved.path_electives,
+            transcript: saved.transcript,
+            repeat_policy: saved.repeat_policy,
+            target: saved.target,
+        }
+    }
+
+    fn saved_from_shared(shared: share::SharedState) -> storage::SavedState {
+        storage::SavedState {
+            calc_type: shared.calc_type,
+            programme: shared.programme,
+            batch: shared.batch,
+            sem_no: shared.sem_no,
+            track: shared.track,
+            calc_cpi_option: shared.cpi_option,
+            grades: shared.grades,
+            electives: shared.electives,
+            prev_cpi: shared.prev_cpi.unwrap_or_default(),
+            current_spi: shared.current_spi.unwrap_or_default(),
+            path_tracks: shared.path_tracks,
+            path_electives: shared.path_electives,
+            transcript: shared.transcript,
+            repeat_policy: shared.repeat_policy,
+            target: shared.target,
+            ..Default::default()
+        }
+    }
+
+    // Puts back a saved state, dropping anything the current curricula no
+    // longer match (e.g. a semester whose course count has changed).
+    fn restore(&mut self, saved: storage::SavedState) {
+        self.calc_type = saved.calc_type;
+        self.programme = saved.programme.filter(|p| semdata::get_programme(p).is_some());
+        self.batch = saved.batch;
+        if self.curriculum().is_none() {
+            self.batch = None;
+            return;
+        }
+        self.sem_no = saved.sem_no;
+        self.track = saved.track;
+        self.calc_cpi_option = saved.calc_cpi_option;
+        self.repeat_policy = saved.repeat_policy;
+        self.path_tracks = saved.path_tracks;
+        self.path_electives = saved.path_electives;
+        self.target = TargetInputs {
+            from_sem: saved.target.from_sem,
+            from_track: saved.target.from_track,
+            current_cpi_str: saved.target.current_cpi,
+            to_sem: saved.target.to_sem,
+            to_track: saved.target.to_track,
+            target_cpi_str: saved.target.target_cpi,
+        };
+
+        // Reopen the saved semester as if 'Done' had been clicked
+        if self.sem_no.is_some() && matches!(self.calc_type, Some(0) | Some(1) | Some(4)) {
+            self.load_semester_data();
+            if self.sem_info.is_some() {
+                self.done_1 = true;
+                if saved.grades.len() == self.grades.len() {
+                    self.grades = saved.grades;
+                }
+                if saved.electives.len() == self.electives.len() && self.electives_fit(self.semester_id(), &saved.electives) {
+                    self.electives = saved.electives;
+                }
+            }
+            self.error_message = None;
+        }
+
+        match (self.calc_type, self.calc_cpi_option) {
+            (Some(4), _) => self.what_if_prev_cpi_str = saved.prev_cpi,
+            (_, Some(2)) => self.cpi_op2_var1_str = saved.prev_cpi,
+            _ => self.cpi_op1_var1_str = saved.prev_cpi,
+        }
+        self.cpi_op2_var2_str = saved.current_spi;
+
+        for (i, row) in saved.transcript.into_iter().enumerate() {
+            let id = SemesterId { number: i as u8 + 1, track: row.track };
+            let fits = match self.curriculum().and_then(|c| c.semester(id)) {
+                Some(sem) => row.grades.len() == sem.course_code.len(),
+                // A semester with tracks whose track had not been picked yet
+                None => row.track.is_none() && row.grades.is_empty() && !self.available_tracks(id.number).is_empty(),
+            };
+            if !fits {
+                break;
+            }
+            let electives = if row.electives.len() == row.grades.len() && self.electives_fit(Some(id), &row.electives) {
+                row.electives
+            } else {
+                vec![None; row.grades.len()]
+            };
+            self.transcript.push(TranscriptRow { track: row.track, grades: row.grades, electives, repeats: row.repeats });
+        }
+
+        if self.calc_type == Some(4) && self.done_1 {
+            self.run_simulation();
+        }
+    }
+
     // Helper function to reset state dependent on semester selection
     fn reset_semester_dependent_state(&mut self) {
         self.sem_info = None;
         self.grades = Vec::new();
+        self.electives = Vec::new();
         self.calculated_spi = None;
         self.calculated_cpi = None;
         self.calc_cpi_option = None; // Reset CPI option as well
@@ -307,21 +886,72 @@ impl App {
         self.cpi_op2_var1_str = "".to_string();
         self.cpi_op2_var2_str = "".to_string();
         self.error_message = None;
+        self.course_errors.clear();
+        self.transcript = Vec::new(); // Courses differ between curricula
+        self.transcript_results = None;
+        self.target = TargetInputs::default();
+        self.target_plan = None;
+        self.what_if_prev_cpi_str = "".to_string();
+        self.simulation = None;
+    }
+
+    // Curriculum for the selected programme and batch
+    fn curriculum(&self) -> Option<&'static semdata::Curriculum> {
+        semdata::get_curriculum(self.programme.as_deref()?, self.batch?)
+    }
+
+    fn available_tracks(&self, sem: u8) -> Vec<Track> {
+        self.curriculum().map(|c| c.tracks(sem)).unwrap_or_default()
+    }
+
+    // Combined semester number and track
+    fn semester_id(&self) -> Option<SemesterId> {
+        self.sem_no.map(|number| SemesterId { number, track: self.track })
+    }
+
+    // Semesters 1 to `number - 1`, along the tracks picked for them
+    fn path_before(&self, number: u8) -> Vec<SemesterId> {
+        match self.curriculum() {
+            Some(curriculum) => form::path_before(curriculum, number, &self.path_tracks),
+            None => Vec::new(),
+        }
+    }
+
+    // Whether `electives` are valid choices for the slots of semester `id`
+    fn electives_fit(&self, id: Option<SemesterId>, electives: &[Option<String>]) -> bool {
+        match (self.curriculum(), id) {
+            (Some(curriculum), Some(id)) => functions::resolve_semester(curriculum, id, electives).is_ok(),
+            _ => false,
+        }
+    }
+
+    // The selected semester with the chosen electives filled in
+    fn resolved_semester(&self) -> Option<semdata::Semester> {
+        let info = self.sem_info.as_ref()?;
+        let curriculum = self.curriculum()?;
+        Some(functions::resolve_semester(curriculum, info.id, &self.electives).unwrap_or_else(|_| info.clone()))
     }
 
     // Helper function to load semester data
     fn load_semester_data(&mut self) {
-        if let Some(sem_f32) = self.sem_no_f32 {
-            match semdata::get_semesters(sem_f32) {
+        if let (Some(programme), Some(batch), Some(sem_id)) = (&self.programme, self.batch, self.semester_id()) {
+            match semdata::get_semesters(programme, batch, sem_id) {
                 Some(info) => {
-                    self.grades = vec!["".to_string(); info.course_code.len()]; // Initialize grades vector
+                    // The simulator starts every course at the top grade so results show right away
+                    let initial = match self.calc_type {
+                        Some(4) => grades::default_scale().grades().first().map(|g| g.letter.clone()).unwrap_or_default(),
+                        _ => "".to_string(),
+                    };
+                    self.grades = vec![initial; info.course_code.len()]; // Initialize grades vector
+                    self.electives = vec![None; info.course_code.len()];
                     self.sem_info = Some(info);
                     self.error_message = None; // Clear previous errors
                 }
                 None => {
                     self.sem_info = None;
                     self.grades = Vec::new();
-                    self.error_message = Some(format!("Could not find data for semester {:.1}", sem_f32));
+                    self.electives = Vec::new();
+                    self.error_message = Some(format!("Could not find data for semester {}", sem_id));
                 }
             }
         }
@@ -335,40 +965,221 @@ impl App {
         match self.calc_type {
             Some(0) => true, // Always show for SPI
             Some(1) => self.sem_no == Some(1) || self.calc_cpi_option == Some(1), // Show for CPI Sem 1 or CPI Option 1
+            Some(4) => true, // What-if simulator edits the same grade rows
             _ => false,
         }
     }
 
     // --- View Helper Functions ---
 
+    fn view_semester_select(&self, link: &html::Scope<Self>) -> Html {
+        let curriculum = match self.curriculum() {
+            Some(c) => c,
+            None => return html! {},
+        };
+        Self::view_semester_picker(
+            curriculum,
+            "semester",
+            "Select Semester:",
+            curriculum.semester_numbers(),
+            (self.sem_no, self.track),
+            link.callback(Msg::SelectSemester),
+            link.callback(Msg::SelectTrack),
+        )
+    }
+
+    // Semester dropdown plus, for semesters offered in several tracks, a
+    // track dropdown. `id` prefixes the element ids.
+    fn view_semester_picker(
+        curriculum: &semdata::Curriculum,
+        id: &str,
+        label: &str,
+        numbers: Vec<u8>,
+        (sem_no, track): (Option<u8>, Option<Track>),
+        on_sem: Callback<Option<u8>>,
+        on_track: Callback<Option<Track>>,
+    ) -> Html {
+        let tracks = sem_no.map(|sem| curriculum.tracks(sem)).unwrap_or_default();
+        html! {
+            <>
+            <label for={id.to_string()}>{ label }</label>
+            <select id={id.to_string()} onchange={move |e: Event| on_sem.emit(e.target_unchecked_into::<HtmlInputElement>().value().parse().ok())}>
+                <option value="" selected={sem_no.is_none()} disabled=true>{ "Select" }</option>
+                { for numbers.into_iter().map(|i| html! { <option value={i.to_string()} selected={sem_no == Some(i)}>{ format!("Semester {}", i) }</option> }) }
+            </select>
+
+            // --- Track Selection (for semesters offered in several tracks) ---
+            { if !tracks.is_empty() { html! {
+                <>
+                <label for={format!("{}_track", id)}>{ format!("Select Track (Sem {}):", sem_no.unwrap_or_default()) }</label>
+                <select id={format!("{}_track", id)} onchange={move |e: Event| on_track.emit(e.target_unchecked_into::<HtmlInputElement>().value().parse().ok())}>
+                    <option value="" selected={track.is_none()} disabled=true>{ "Select" }</option>
+                    { for tracks.into_iter().map(|t| html! { <option value={t.id()} selected={track == Some(t)}>{ t.label() }</option> }) }
+                </select>
+                </>
+            }} else { html!{} }}
+            </>
+        }
+    }
+
+    // For each semester of `path`, a track dropdown where it had several
+    // tracks (unless `pick_track` leaves it to another picker) and the electives
+    // taken in it, so CPI is weighted by the credits actually taken.
+    fn view_path_choices(&self, link: &html::Scope<Self>, path: &[SemesterId], pick_track: impl Fn(u8) -> bool) -> Html {
+        let curriculum = match self.curriculum() {
+            Some(c) => c,
+            None => return html! {},
+        };
+        html! {
+            { for path.iter().map(|&id| {
+                let number = id.number;
+                let track_select = if pick_track(number) && !self.available_tracks(number).is_empty() {
+                    let track = self.path_tracks.get(&number).copied();
+                    let select_id = format!("path_track_{}", number);
+                    html! {
+                        <>
+                        <label for={select_id.clone()}>{ format!("Track taken in Semester {}:", number) }</label>
+                        <select id={select_id} onchange={link.callback(move |e: Event| Msg::SelectPathTrack(number, e.target_unchecked_into::<HtmlInputElement>().value().parse().ok()))}>
+                            <option value="" selected={track.is_none()} disabled=true>{ "Select" }</option>
+                            { for self.available_tracks(number).into_iter().map(|t| html! { <option value={t.id()} selected={track == Some(t)}>{ t.label() }</option> }) }
+                        </select>
+                        </>
+                    }
+                } else {
+                    html! {}
+                };
+                let elective_selects = match curriculum.semester(id) {
+                    Some(sem) => html! {
+                        { for (0..sem.course_code.len()).map(|index| Self::view_elective_select(
+                            &format!("path_elective_{}_{}", number, index),
+                            &format!("Semester {} {}:", number, sem.course_name[index]),
+                            curriculum.electives_for(id, index),
+                            form::path_elective(&self.path_electives, number, index),
+                            link.callback(move |code| Msg::SelectPathElective(number, index, code)),
+                        )) }
+                    },
+                    None => html! {},
+                };
+                html! { <>{ track_select }{ elective_selects }</> }
+            }) }
+        }
+    }
+
+    fn view_batch_select(&self, link: &html::Scope<Self>) -> Html {
+        let programme = match self.programme.as_deref().and_then(semdata::get_programme) {
+            Some(p) => p,
+            None => return html! {},
+        };
+        // Offer every admission year up to the current one that has a curriculum
+        let current_year = js_sys::Date::new_0().get_full_year() as u16;
+        html! {
+            <>
+            <label for="batch">{ "Select Batch (Admission Year):" }</label>
+            <select id="batch" onchange={link.callback(|e: Event| Msg::SelectBatch(e.target_unchecked_into::<HtmlInputElement>().value()))}>
+                <option value="" selected={self.batch.is_none()} disabled=true>{ "Select" }</option>
+                { for programme.batches(current_year).into_iter().rev().map(|year| html! { <option value={year.to_string()} selected={self.batch == Some(year)}>{ year }</option> }) }
+            </select>
+            </>
+        }
+    }
+
     fn view_grade_input(&self, link: &html::Scope<Self>) -> Html {
-        match &self.sem_info {
-            Some(info) => html! {
+        match (self.resolved_semester(), self.curriculum()) {
+            (Some(sem), Some(curriculum)) => html! {
                 <div>
-                    <h3>{ format!("Enter Grades for Semester {:.1}", self.sem_no_f32.unwrap_or(0.0)) }</h3>
-                    { for info.course_code.iter().enumerate().map(|(i, code)| self.view_course_grade_input(link, i, code, &info.course_name[i], info.course_credit[i])) }
+                    <h3>{ format!("Enter Grades for Semester {}", sem.id) }</h3>
+                    <p>{ format!("Total Credits: {}", sem.total_credit) }</p>
+                    { self.view_import(link) }
+                    { for (0..sem.course_code.len()).map(|i| self.view_course_grade_input(link, curriculum, &sem, i)) }
                 </div>
             },
-            None => html! { <p>{ "Select semester and click 'Done' to load courses." }</p> },
+            _ => html! { <p>{ "Select semester and click 'Done' to load courses." }</p> },
         }
     }
 
-     fn view_course_grade_input(&self, link: &html::Scope<Self>, index: usize, code: &str, name: &str, credit: f32) -> Html {
+     fn view_course_grade_input(&self, link: &html::Scope<Self>, curriculum: &semdata::Curriculum, sem: &semdata::Semester, index: usize) -> Html {
         let grade_value = self.grades.get(index).cloned().unwrap_or_default();
+        let id = format!("grade-{}", index);
+        let picker = Self::view_elective_select(
+            &format!("{}-elective", id),
+            "Elective Course:",
+            curriculum.electives_for(sem.id, index),
+            self.electives.get(index).cloned().flatten(),
+            link.callback(move |code| Msg::SelectElective(index, code)),
+        );
+        Self::view_course_row(&id, sem, index, &grade_value, self.course_errors.get(&id), picker, link.callback(move |grade| Msg::UpdateGrade(index, grade)))
+    }
+
+    // Course `index` of `sem` with its letter-grade dropdown; `on_grade` receives the chosen letter.
+    // `picker` is the elective choice for placeholder slots. `error` is shown under the
+    // dropdown when the last calculation rejected the grade.
+    fn view_course_row(id: &str, sem: &semdata::Semester, index: usize, grade_value: &str, error: Option<&String>, picker: Html, on_grade: Callback<String>) -> Html {
         html! {
             <div class="course-item">
-                <div class="course-details">{ format!("{} - {} (Credits: {})", code, name, credit) }</div>
-                <label for={format!("grade-{}", index)}>{ "Enter Grade (0-10):" }</label>
+                <div class="course-details">{ format!("{} - {} (Credits: {})", sem.course_code[index], sem.course_name[index], sem.course_credit[index]) }</div>
+                { picker }
+                <label for={id.to_string()}>{ "Select Grade:" }</label>
+                { Self::view_grade_select(id, grade_value, on_grade) }
+                { if let Some(err) = error { html! { <p class="course-error">{ err }</p> } } else { html!{} } }
+            </div>
+        }
+    }
+
+    // Catalog courses for a placeholder slot; nothing for a regular course.
+    fn view_elective_select(id: &str, label: &str, options: Vec<&semdata::Elective>, selected: Option<String>, on_select: Callback<Option<String>>) -> Html {
+        if options.is_empty() {
+            return html! {};
+        }
+        html! {
+            <>
+            <label for={id.to_string()}>{ label }</label>
+            <select
+                id={id.to_string()}
+                onchange={move |e: Event| on_select.emit(Some(e.target_unchecked_into::<HtmlInputElement>().value()).filter(|code| !code.is_empty()))}
+            >
+                <option value="" selected={selected.is_none()}>{ "Not chosen" }</option>
+                { for options.into_iter().map(|e| html! {
+                    <option value={e.code.clone()} selected={selected.as_deref() == Some(e.code.as_str())}>{ format!("{} - {} (Credits: {})", e.code, e.name, e.credit) }</option>
+                }) }
+            </select>
+            </>
+        }
+    }
+
+    fn view_grade_select(id: &str, grade_value: &str, on_grade: Callback<String>) -> Html {
+        html! {
+            <select
+                id={id.to_string()}
+                onchange={move |e: Event| on_grade.emit(e.target_unchecked_into::<HtmlInputElement>().value())}
+            >
+                <option value="" selected={grade_value.is_empty()} disabled=true>{ "Select" }</option>
+                { for grades::default_scale().grades().iter().map(|g| html! {
+                    <option value={g.letter.clone()} selected={g.letter == grade_value}>{ format!("{} ({})", g.letter, g.points) }</option>
+                }) }
+            </select>
+        }
+    }
+
+     // Paste box and file picker that fill in grades instead of picking them one by one
+    fn view_import(&self, link: &html::Scope<Self>) -> Html {
+        html! {
+            <div class="import-grades">
+                <label for="import_text">{ "Import Grades (paste from the portal, or choose a CSV/JSON file):" }</label>
+                <textarea
+                    id="import_text"
+                    rows="4"
+                    placeholder="CS201  Data Structures  8  AB"
+                    value={self.import_text.clone()}
+                    oninput={link.callback(|e: InputEvent| Msg::UpdateImportText(e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value()))}
+                />
                 <input
-                    id={format!("grade-{}", index)}
-                    type="number"
-                    min="0"
-                    max="10"
-                    step="1" // Or "0.1" if decimal grades are allowed by logic
-                    placeholder="e.g., 8"
-                    value={grade_value}
-                    oninput={link.callback(move |e: InputEvent| Msg::UpdateGrade(index, e.target_unchecked_into::<HtmlInputElement>().value()))}
+                    id="import_file"
+                    type="file"
+                    accept=".csv,.json,.txt"
+                    onchange={link.batch_callback(|e: Event| e.target_unchecked_into::<HtmlInputElement>().files().and_then(|files| files.get(0)).map(Msg::ImportFile))}
                 />
+                <button onclick={link.callback(|_| Msg::ImportGrades)}>{ "Import" }</button>
+                { if let Some(notice) = &self.import_notice { html! { <p class="import-notice">{ notice }</p> } } else { html!{} } }
             </div>
         }
     }
@@ -378,6 +1189,7 @@ impl App {
         html! {
             <div>
                 <h3>{ "Calculate CPI Options" }</h3>
+                { self.view_path_choices(link, &self.path_before(sem), |_| true) }
                 <div>
                     <input
                         type="radio"
@@ -448,6 +1260,399 @@ impl App {
         }
     }
 
+    fn view_transcript_editor(&self, link: &html::Scope<Self>) -> Html {
+        let curriculum = match self.curriculum() {
+            Some(c) => c,
+            None => return html! {},
+        };
+        let next = self.transcript.len() as u8 + 1;
+        html! {
+            <div>
+                <h3>{ "Transcript" }</h3>
+                <label for="repeat_policy">{ "Cleared Backlogs Count in CPI As:" }</label>
+                <select id="repeat_policy" onchange={link.callback(|e: Event| Msg::SelectRepeatPolicy(e.target_unchecked_into::<HtmlInputElement>().value()))}>
+                    { for RepeatPolicy::ALL.into_iter().map(|p| html! { <option value={p.id()} selected={self.repeat_policy == p}>{ p.label() }</option> }) }
+                </select>
+                { self.view_import(link) }
+                { for self.transcript.iter().enumerate().map(|(i, row)| self.view_transcript_semester(link, curriculum, i, row)) }
+                { if curriculum.semester_numbers().contains(&next) { html! {
+                    <button onclick={link.callback(|_| Msg::AddTranscriptSemester)}>{ format!("Add Semester {}", next) }</button>
+                }} else { html!{} }}
+                { if !self.transcript.is_empty() { html! {
+                    <button onclick={link.callback(|_| Msg::RemoveTranscriptSemester)} class="reset-button">{ "Remove Last Semester" }</button>
+                }} else { html!{} }}
+            </div>
+        }
+    }
+
+    fn view_transcript_semester(&self, link: &html::Scope<Self>, curriculum: &semdata::Curriculum, index: usize, row: &TranscriptRow) -> Html {
+        let number = index as u8 + 1;
+        let tracks = curriculum.tracks(number);
+        let sem_id = SemesterId { number, track: row.track };
+        let semester = curriculum
+            .semester(sem_id)
+            .map(|sem| functions::resolve_semester(curriculum, sem_id, &row.electives).unwrap_or_else(|_| sem.clone()));
+        html! {
+            <div class="transcript-semester">
+                <h4>{ format!("Semester {}", number) }</h4>
+                { if !tracks.is_empty() { html! {
+                    <>
+                    <label for={format!("transcript-track-{}", index)}>{ "Select Track:" }</label>
+                    <select id={format!("transcript-track-{}", index)} onchange={link.callback(move |e: Event| Msg::SelectTranscriptTrack(index, e.target_unchecked_into::<HtmlInputElement>().value().parse().ok()))}>
+                        <option value="" selected={row.track.is_none()} disabled=true>{ "Select" }</option>
+                        { for tracks.into_iter().map(|t| html! { <option value={t.id()} selected={row.track == Some(t)}>{ t.label() }</option> }) }
+                    </select>
+                    </>
+                }} else { html!{} }}
+                { if let Some(sem) = semester { html! {
+                    { for (0..sem.course_code.len()).map(|course| {
+                        let id = format!("transcript-grade-{}-{}", index, course);
+                        let picker = Self::view_elective_select(
+                            &format!("{}-elective", id),
+                            "Elective Course:",
+                            curriculum.electives_for(sem_id, course),
+                            row.electives.get(course).cloned().flatten(),
+                            link.callback(move |code| Msg::SelectTranscriptElective(index, course, code)),
+                        );
+                        Self::view_course_row(
+                            &id,
+                            &sem,
+                            course,
+                            row.grades.get(course).map(String::as_str).unwrap_or_default(),
+                            self.course_errors.get(&id),
+                            picker,
+                            link.callback(move |grade| Msg::UpdateTranscriptGrade(index, course, grade)),
+                        )
+                    }) }
+                }} else { html!{} }}
+                { self.view_repeats(link, index, row) }
+            </div>
+        }
+    }
+
+    // Backlogs retaken in the semester at `index`, chosen from the fails still
+    // outstanding after the earlier semesters.
+    fn view_repeats(&self, link: &html::Scope<Self>, index: usize, row: &TranscriptRow) -> Html {
+        let backlogs = self.backlogs_before(index);
+        if backlogs.is_empty() && row.repeats.is_empty() {
+            return html! {};
+        }
+        let can_add = backlogs.iter().any(|b| !row.repeats.iter().any(|r| r.course == b.course));
+        html! {
+            <>
+            { for row.repeats.iter().enumerate().map(|(repeat, r)| {
+                let id = format!("transcript-repeat-{}-{}", index, repeat);
+                html! {
+                    <div class="course-item">
+                        <label for={format!("{}-course", id)}>{ "Backlog Course:" }</label>
+                        <select
+                            id={format!("{}-course", id)}
+                            onchange={link.callback(move |e: Event| Msg::SelectRepeatCourse(index, repeat, e.target_unchecked_into::<HtmlInputElement>().value()))}
+                        >
+                            { for backlogs.iter().map(|b| html! {
+                                <option value={format!("{}-{}", b.course.semester, b.course.course)} selected={b.course == r.course}>
+                                    { format!("Sem {}: {} - {} (Credits: {})", b.course.semester, b.code, b.name, b.credit) }
+                                </option>
+                            }) }
+                        </select>
+                        <label for={id.clone()}>{ "Select Grade:" }</label>
+                        { Self::view_grade_select(&id, &r.grade, link.callback(move |grade| Msg::UpdateRepeatGrade(index, repeat, grade))) }
+                        { if let Some(err) = self.course_errors.get(&id) { html! { <p class="course-error">{ err }</p> } } else { html!{} } }
+                        <button onclick={link.callback(move |_| Msg::RemoveRepeat(index, repeat))} class="reset-button">{ "Remove" }</button>
+                    </div>
+                }
+            }) }
+            { if can_add { html! {
+                <button onclick={link.callback(move |_| Msg::AddRepeat(index))}>{ "Add Cleared Backlog" }</button>
+            }} else { html!{} }}
+            </>
+        }
+    }
+
+    fn view_target_planner(&self, link: &html::Scope<Self>) -> Html {
+        let curriculum = match self.curriculum() {
+            Some(c) => c,
+            None => return html! {},
+        };
+        let numbers = curriculum.semester_numbers();
+        let last = numbers.last().copied().unwrap_or_default();
+        let from_numbers: Vec<u8> = numbers.iter().copied().filter(|&n| n < last).collect();
+        let to_numbers: Vec<u8> = numbers.iter().copied().filter(|&n| n > self.target.from_sem.unwrap_or(0)).collect();
+        html! {
+            <div>
+                <h3>{ "Target CPI Planner" }</h3>
+                { Self::view_semester_picker(
+                    curriculum,
+                    "target_from",
+                    "Semester Completed:",
+                    from_numbers,
+                    (self.target.from_sem, self.target.from_track),
+                    link.callback(Msg::SelectTargetFromSemester),
+                    link.callback(Msg::SelectTargetFromTrack),
+                ) }
+                <label for="target_current_cpi">{ "Current CPI:" }</label>
+                <input
+                    id="target_current_cpi"
+                    type="number"
+                    step="any"
+                    placeholder="e.g., 8.2"
+                    value={self.target.current_cpi_str.clone()}
+                    oninput={link.callback(|e: InputEvent| Msg::UpdateTargetCurrentCpi(e.target_unchecked_into::<HtmlInputElement>().value()))}
+                />
+                { Self::view_semester_picker(
+                    curriculum,
+                    "target_to",
+                    "Reach Target By:",
+                    to_numbers,
+                    (self.target.to_sem, self.target.to_track),
+                    link.callback(Msg::SelectTargetToSemester),
+                    link.callback(Msg::SelectTargetToTrack),
+                ) }
+                // Semesters in between (and before) count the tracks taken in them
+                { self.view_path_choices(link, &self.target_path().unwrap_or_default(), |n| Some(n) != self.target.from_sem && Some(n) != self.target.to_sem) }
+                <label for="target_cpi">{ "Target CPI:" }</label>
+                <input
+                    id="target_cpi"
+                    type="number"
+                    step="any"
+                    placeholder="e.g., 8.5"
+                    value={self.target.target_cpi_str.clone()}
+                    oninput={link.callback(|e: InputEvent| Msg::UpdateTargetCpi(e.target_unchecked_into::<HtmlInputElement>().value()))}
+                />
+            </div>
+        }
+    }
+
+    fn view_target_plan(&self) -> Html {
+        let plan = match &self.target_plan {
+            Some(p) => p,
+            None => return html! {},
+        };
+        let message = if plan.impossible {
+            format!("Not reachable: it would need an average SPI of {:.3} over the next {} credits, above the maximum of 10.", plan.required_spi, plan.remaining_credits)
+        } else if plan.required_spi <= 0.0 {
+            "Target already secured: any SPI keeps you at or above it.".to_string()
+        } else {
+            format!("Required average SPI: {:.3} over the next {} credits", plan.required_spi, plan.remaining_credits)
+        };
+        html! { <div class={if plan.impossible { "error" } else { "result" }}>{ message }</div> }
+    }
+
+    fn view_simulation(&self, link: &html::Scope<Self>) -> Html {
+        let info = match self.resolved_semester() {
+            Some(info) if self.calc_type == Some(4) && self.done_1 => info,
+            _ => return html! {},
+        };
+        let step_text = |step: &Option<functions::GradeStep>| match step {
+            Some(s) => format!("{}: {:+.3} / {:+.3}", s.grade, s.spi_delta, s.cpi_delta),
+            None => "-".to_string(),
+        };
+        html! {
+            <div>
+                <h3>{ "What-If Results" }</h3>
+                { if self.sem_no.is_some_and(|s| s > 1) { html! {
+                    <>
+                    <label for="what_if_prev_cpi">{ format!("CPI up to Semester {} (for CPI):", self.sem_no.unwrap_or(1) - 1) }</label>
+                    <input
+                        id="what_if_prev_cpi"
+                        type="number"
+                        step="any"
+                        placeholder="e.g., 8.5"
+                        value={self.what_if_prev_cpi_str.clone()}
+                        oninput={link.callback(|e: InputEvent| Msg::UpdateWhatIfPrevCpi(e.target_unchecked_into::<HtmlInputElement>().value()))}
+                    />
+                    { self.view_path_choices(link, &self.path_before(self.sem_no.unwrap_or(1)), |_| true) }
+                    </>
+                }} else { html!{} }}
+                { match &self.simulation {
+                    Some(sim) => html! {
+                        <>
+                        <div class="result">
+                            { format!("Expected SPI: {:.3}", sim.spi) }
+                            { match sim.cpi {
+                                Some(cpi) => format!(" | Expected CPI: {:.3}", cpi),
+                                None => "".to_string(),
+                            } }
+                        </div>
+                        <table class="grade-table">
+                            <thead>
+                                <tr>
+                                    <th>{ "Course" }</th>
+                                    <th>{ "Credits" }</th>
+                                    <th>{ "Grade" }</th>
+                                    <th>{ "One Grade Up (SPI / CPI)" }</th>
+                                    <th>{ "One Grade Down (SPI / CPI)" }</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for sim.courses.iter().map(|c| html! {
+                                    <tr>
+                                        <td>{ &info.course_code[c.index] }</td>
+                                        <td>{ info.course_credit[c.index] }</td>
+                                        <td>{ self.grades.get(c.index).cloned().unwrap_or_default() }</td>
+                                        <td>{ step_text(&c.up) }</td>
+                                        <td>{ step_text(&c.down) }</td>
+                                    </tr>
+                                }) }
+                            </tbody>
+                        </table>
+                        <h4>{ "Most CPI per grade point" }</h4>
+                        <ol>
+                            { for sim.ranking().into_iter().map(|c| html! {
+                                <li>{ format!("{} - {}: {:.4} CPI (SPI {:.4}) per grade point", info.course_code[c.index], info.course_name[c.index], c.cpi_per_point, c.spi_per_point) }</li>
+                            }) }
+                        </ol>
+                        </>
+                    },
+                    None => html! {},
+                } }
+            </div>
+        }
+    }
+
+    fn view_transcript_results(&self, link: &html::Scope<Self>) -> Html {
+        let results = match &self.transcript_results {
+            Some(r) => r,
+            None => return html! {},
+        };
+        html! {
+            <div class="result">
+                <table class="grade-table">
+                    <thead>
+                        <tr><th>{ "Semester" }</th><th>{ "Credits" }</th><th>{ "SPI" }</th><th>{ "CPI" }</th><th>{ "Backlogs" }</th></tr>
+                    </thead>
+                    <tbody>
+                        { for results.iter().map(|r| html! {
+                            <tr>
+                                <td>{ r.semester.to_string() }</td>
+                                <td>{ r.credits }</td>
+                                <td>{ format!("{:.3}", r.spi) }</td>
+                                <td>{ format!("{:.3}", r.cpi) }</td>
+                                <td>{ r.backlogs.len() }</td>
+                            </tr>
+                        }) }
+                    </tbody>
+                </table>
+                <label for="chart_target">{ "Target CPI (drawn on the chart):" }</label>
+                <input
+                    id="chart_target"
+                    type="number"
+                    step="any"
+                    placeholder="e.g., 8.5"
+                    value={self.chart_target_str.clone()}
+                    oninput={link.callback(|e: InputEvent| Msg::UpdateChartTarget(e.target_unchecked_into::<HtmlInputElement>().value()))}
+                />
+                { chart::trend_chart(results, self.chart_target_str.parse::<f32>().ok().filter(|c| (0.0..=10.0).contains(c))) }
+                { for results.iter().map(|r| html! {
+                    <details>
+                        <summary>{ format!("Semester {} courses", r.semester) }</summary>
+                        <ul>
+                            { for r.courses.iter().map(|c| html! {
+                                <li>{ format!("{} - {} ({} credits): {}{}", c.code, c.name, c.credit, c.grade, if c.repeat { " (repeat)" } else { "" }) }</li>
+                            }) }
+                        </ul>
+                    </details>
+                }) }
+            </div>
+        }
+    }
+
+    // The SPI/CPI or transcript on screen, per course; None when there is
+    // nothing to download
+    fn export(&self) -> Option<export::Export> {
+        let (programme, batch) = (self.programme.clone()?, self.batch?);
+        let scale = grades::default_scale();
+        let semesters = match (&self.transcript_results, self.calculated_spi) {
+            (Some(results), _) => results.iter().map(|r| export::ExportSemester::from_result(r, scale)).collect(),
+            (None, Some(spi)) => {
+                // CPI Option 2 takes the SPI as entered, without grades
+                let grades: &[String] = if self.should_show_grade_input() { &self.grades } else { &[] };
+                vec![export::ExportSemester::from_grades(&self.resolved_semester()?, grades, scale, spi, self.calculated_cpi).ok()?]
+            }
+            _ => return None,
+        };
+        Some(export::Export { programme, batch, semesters })
+    }
+
+    // The results as a transcript to print or save as PDF, built from the same
+    // per-course data as the downloads. Name and roll number are typed in here.
+    fn view_report(&self, link: &html::Scope<Self>, export: &export::Export) -> Html {
+        let programme = semdata::get_programme(&export.programme).map_or(export.programme.as_str(), |p| p.name.as_str());
+        let today = js_sys::Date::new_0().to_locale_date_string("en-IN", &JsValue::UNDEFINED);
+        let detail = |label: &str, value: &str| {
+            if value.trim().is_empty() { html! {} } else { html! { <tr><th>{ label }</th><td>{ value.trim().to_string() }</td></tr> } }
+        };
+        html! {
+            <div id="app-container" class="report-page">
+                <div class="report-controls no-print">
+                    <label for="student_name">{ "Name:" }</label>
+                    <input id="student_name" type="text" value={self.student_name.clone()}
+                        oninput={link.callback(|e: InputEvent| Msg::UpdateStudentName(e.target_unchecked_into::<HtmlInputElement>().value()))} />
+                    <label for="roll_number">{ "Roll Number:" }</label>
+                    <input id="roll_number" type="text" value={self.roll_number.clone()}
+                        oninput={link.callback(|e: InputEvent| Msg::UpdateRollNumber(e.target_unchecked_into::<HtmlInputElement>().value()))} />
+                    <div>
+                        <button onclick={link.callback(|_| Msg::PrintReport)}>{ "Print / Save as PDF" }</button>
+                        <button onclick={link.callback(|_| Msg::ShowReport(false))} class="reset-button">{ "Back to Calculator" }</button>
+                    </div>
+                </div>
+
+                <div class="report">
+                    <h2>{ "Grade Report" }</h2>
+                    <table class="report-details">
+                        { detail("Name", &self.student_name) }
+                        { detail("Roll Number", &self.roll_number) }
+                        { detail("Programme", programme) }
+                        { detail("Batch", &export.batch.to_string()) }
+                    </table>
+                    { for export.semesters.iter().map(Self::view_report_semester) }
+                    <p class="report-footer">{ format!("Prepared on {} from the grades entered in the CPI / SPI Calculator.", String::from(today)) }</p>
+                </div>
+            </div>
+        }
+    }
+
+    fn view_report_semester(sem: &export::ExportSemester) -> Html {
+        let title = match sem.track {
+            Some(track) => format!("Semester {} ({})", sem.semester, track.label()),
+            None => format!("Semester {}", sem.semester),
+        };
+        html! {
+            <div class="report-semester">
+                <h3>{ title }</h3>
+                <table>
+                    <thead>
+                        <tr><th>{ "Code" }</th><th>{ "Course" }</th><th class="number">{ "Credits" }</th><th>{ "Grade" }</th><th class="number">{ "Grade Points" }</th></tr>
+                    </thead>
+                    <tbody>
+                        { if sem.courses.is_empty() { html! {
+                            <tr><td colspan="5">{ "Courses not entered; SPI given directly." }</td></tr>
+                        }} else { html!{} }}
+                        { for sem.courses.iter().map(|c| html! {
+                            <tr>
+                                <td>{ &c.code }</td>
+                                <td>{ if c.repeat { format!("{} (repeat)", c.name) } else { c.name.clone() } }</td>
+                                <td class="number">{ c.credit }</td>
+                                <td>{ &c.grade }</td>
+                                <td class="number">{ c.grade_points }</td>
+                            </tr>
+                        }) }
+                    </tbody>
+                    <tfoot>
+                        <tr>
+                            <td colspan="2">{ "Semester credits" }</td>
+                            <td class="number">{ sem.credits }</td>
+                            <td colspan="2">
+                                { format!("SPI {:.3}", sem.spi) }
+                                { if let Some(cpi) = sem.cpi { format!(" \u{b7} CPI {:.3}", cpi) } else { String::new() } }
+                            </td>
+                        </tr>
+                    </tfoot>
+                </table>
+            </div>
+        }
+    }
+
     fn view_results(&self) -> Html {
         html! {
             <>
@@ -478,14 +1683,9 @@ impl App {
                             <tr><th>{ "Grade" }</th><th>{ "Points" }</th></tr>
                         </thead>
                         <tbody>
-                            <tr><td>{ "AA" }</td><td>{ "10" }</td></tr>
-                            <tr><td>{ "AB" }</td><td>{ "9" }</td></tr>
-                            <tr><td>{ "BB" }</td><td>{ "8" }</td></tr>
-                            <tr><td>{ "BC" }</td><td>{ "7" }</td></tr>
-                            <tr><td>{ "CC" }</td><td>{ "6" }</td></tr>
-                            <tr><td>{ "CD" }</td><td>{ "5" }</td></tr>
-                            <tr><td>{ "DD" }</td><td>{ "4" }</td></tr>
-                            <tr><td>{ "FF" }</td><td>{ "0" }</td></tr>
+                            { for grades::default_scale().grades().iter().map(|g| html! {
+                                <tr><td>{ &g.letter }</td><td>{ g.points }</td></tr>
+                            }) }
                         </tbody>
                     </table>
                 </div>
@@ -496,104 +1696,383 @@ impl App {
     }
 
     // --- Calculation Logic ---
+    // Fills grades (and electives) from `import_text`: the loaded semester, or
+    // every transcript semester, adding rows for imported semesters not yet listed
+    fn import_grades(&mut self) {
+        let curriculum = match self.curriculum() {
+            Some(c) => c,
+            None => return,
+        };
+        let scale = grades::default_scale();
+        let imported = match import::parse(&self.import_text, scale) {
+            Ok(imported) => imported,
+            Err(err) => {
+                self.error_message = Some(format!("Could not import grades: {}.", err));
+                return;
+            }
+        };
+        let report = if self.calc_type == Some(2) {
+            let last = imported.iter().filter_map(|g| g.semester).filter(|n| curriculum.semester_numbers().contains(n)).max().unwrap_or(0);
+            while (self.transcript.len() as u8) < last {
+                self.add_transcript_semester();
+            }
+            let mut semesters: Vec<import::SemesterGrades> = self
+                .transcript
+                .iter()
+                .enumerate()
+                .map(|(i, row)| import::SemesterGrades {
+                    id: SemesterId { number: i as u8 + 1, track: row.track },
+                    grades: row.grades.clone(),
+                    electives: row.electives.clone(),
+                })
+                .collect();
+            let report = import::fill_grades(curriculum, &mut semesters, &imported, scale);
+            for (row, sem) in self.transcript.iter_mut().zip(semesters) {
+                row.track = sem.id.track;
+                row.grades = sem.grades;
+                row.electives = sem.electives;
+            }
+            report
+        } else {
+            let id = match (&self.sem_info, self.should_show_grade_input()) {
+                (Some(info), true) => info.id,
+                _ => return,
+            };
+            let mut semesters = [import::SemesterGrades { id, grades: self.grades.clone(), electives: self.electives.clone() }];
+            let report = import::fill_grades(curriculum, &mut semesters, &imported, scale);
+            let [sem] = semesters;
+            self.grades = sem.grades;
+            self.electives = sem.electives;
+            report
+        };
+        self.import_notice = Some(Self::import_summary(&report));
+    }
+
+    fn import_summary(report: &import::ImportReport) -> String {
+        let mut parts = vec![format!("Imported {} grade{}.", report.matched, if report.matched == 1 { "" } else { "s" })];
+        if !report.unmatched.is_empty() {
+            parts.push(format!("Not found in this curriculum: {}.", report.unmatched.join(", ")));
+        }
+        if !report.ambiguous.is_empty() {
+            parts.push(format!("Ambiguous (several semesters, or conflicting grades), left out: {}.", report.ambiguous.join(", ")));
+        }
+        if !report.invalid.is_empty() {
+            parts.push(format!("Grade not on the scale: {}.", report.invalid.join(", ")));
+        }
+        if report.other_semesters > 0 {
+            parts.push(format!("{} entries for other semesters were skipped.", report.other_semesters));
+        }
+        parts.join(" ")
+    }
+
     fn calculate_results(&mut self) {
         // Clear previous results before new calculation
         self.calculated_spi = None;
         self.calculated_cpi = None;
+        self.transcript_results = None;
         self.error_message = None;
+        self.course_errors.clear();
 
-        let sem_f32 = match self.sem_no_f32 {
+        if self.calc_type == Some(2) {
+            self.calculate_transcript();
+            return;
+        }
+        if self.calc_type == Some(3) {
+            self.calculate_target_plan();
+            return;
+        }
+
+        let sem_id = match self.semester_id() {
             Some(s) => s,
             None => {
                 self.error_message = Some("Semester not selected.".to_string());
                 return;
             }
         };
+        let (programme, batch) = match (self.programme.as_deref(), self.batch) {
+            (Some(p), Some(b)) => (p, b),
+            _ => {
+                self.error_message = Some("Programme or batch not selected.".to_string());
+                return;
+            }
+        };
+        if !self.done_1 {
+            self.error_message = Some("Please select semester/options and click 'Done' first.".to_string());
+            return;
+        }
+        let cpi = match self.calc_type {
+            Some(0) => false,
+            Some(1) => true,
+            _ => {
+                self.error_message = Some("Please select calculation type (SPI or CPI).".to_string());
+                return;
+            }
+        };
+        let prev_cpi = match self.calc_cpi_option {
+            Some(2) => &self.cpi_op2_var1_str,
+            _ => &self.cpi_op1_var1_str,
+        };
+        let inputs = SemesterForm {
+            programme,
+            batch,
+            semester: sem_id,
+            grades: &self.grades,
+            electives: &self.electives,
+            cpi,
+            cpi_option: self.calc_cpi_option,
+            prev_cpi,
+            current_spi: &self.cpi_op2_var2_str,
+            path_tracks: &self.path_tracks,
+            path_electives: &self.path_electives,
+        };
+        match inputs.calculate(grades::default_scale()) {
+            Ok(result) => {
+                self.calculated_spi = Some(result.spi);
+                self.calculated_cpi = result.cpi;
+            }
+            Err(FormError::Grades(errors)) => {
+                let rows = errors.iter().map(|(i, err)| (format!("grade-{}", i), form::course_message(err))).collect();
+                self.mark_grade_rows(rows);
+            }
+            Err(FormError::PrevCpi(_)) => {
+                self.error_message = Some(format!("Invalid previous CPI value entered for Option {}.", self.calc_cpi_option.unwrap_or(1)));
+            }
+            Err(FormError::CurrentSpi(_)) => {
+                self.error_message = Some("Invalid current SPI value entered for Option 2.".to_string());
+            }
+            Err(FormError::NoCpiOption) => {
+                self.error_message = Some("Please select a CPI calculation option.".to_string());
+            }
+            Err(FormError::Calc(err)) => self.show_calc_error(err),
+        }
+    }
+
+    // Recomputes the what-if results from the current grade rows. A missing or
+    // unparsable previous CPI only hides the CPI figures; anything else that
+    // stops the simulation is reported as Calculate reports it.
+    fn run_simulation(&mut self) {
+        let result = match (self.programme.as_deref(), self.batch, self.semester_id()) {
+            (Some(programme), Some(batch), Some(sem_id)) => {
+                let prev_cpi = self.what_if_prev_cpi_str.parse::<f32>().ok().filter(|c| (0.0..=10.0).contains(c));
+                let path = self.path_before(sem_id.number);
+                let path_electives = form::path_electives(&path, &self.path_electives);
+                functions::simulate_semester(programme, batch, sem_id, &self.electives, &path, &path_electives, &self.grades, prev_cpi, grades::default_scale())
+            }
+            _ => {
+                self.simulation = None;
+                return;
+            }
+        };
+        // Messages from the previous run no longer apply
+        self.error_message = None;
+        self.course_errors.clear();
+        self.simulation = match result {
+            Ok(simulation) => Some(simulation),
+            Err(err) => {
+                self.show_calc_error(err);
+                None
+            }
+        };
+    }
 
-        // --- SPI Calculation (Needed for SPI mode, CPI Sem 1, CPI Option 1) ---
-        let spi_result: Option<f32> = if self.calc_type == Some(0) || (self.calc_type == Some(1) && (self.sem_no == Some(1) || self.calc_cpi_option == Some(1))) {
-             if self.grades.iter().any(|g| g.is_empty()) {
-                self.error_message = Some("Please enter all grades.".to_string());
-                None // Indicate error by returning None
-            } else {
-                 match self.grades.iter().map(|g| g.parse::<f32>()).collect::<Result<Vec<f32>, _>>() {
-                    Ok(_parsed_grades) => { // Use _parsed_grades if calculate_spi_n is used later
-                        // Using calc_spi which takes Vec<String> as per original logic
-                        let spi = functions::calc_spi(sem_f32, self.grades.clone());
-                        Some(spi)
-                    }
-                    Err(_) => {
-                        self.error_message = Some("Invalid grade(s) entered. Please use numbers between 0 and 10.".to_string());
-                        None
-                    }
-                }
+    // Semesters 1 to the target one; the completed and target semesters use
+    // the tracks picked next to them
+    fn target_path(&self) -> Option<Vec<SemesterId>> {
+        let to_sem = self.target.to_sem?;
+        let mut path = self.path_before(to_sem);
+        if let Some(current) = self.target.from_sem.and_then(|n| (n as usize).checked_sub(1)).and_then(|i| path.get_mut(i)) {
+            current.track = self.target.from_track;
+        }
+        path.push(SemesterId { number: to_sem, track: self.target.to_track });
+        Some(path)
+    }
+
+    fn calculate_target_plan(&mut self) {
+        let (programme, batch) = match (self.programme.clone(), self.batch) {
+            (Some(p), Some(b)) => (p, b),
+            _ => {
+                self.error_message = Some("Programme or batch not selected.".to_string());
+                return;
+            }
+        };
+        let inputs = &self.target;
+        let (from_sem, to_sem) = match (inputs.from_sem, inputs.to_sem) {
+            (Some(from), Some(to)) => (from, to),
+            _ => {
+                self.error_message = Some("Please select both semesters.".to_string());
+                return;
+            }
+        };
+        for (sem, track) in [(from_sem, inputs.from_track), (to_sem, inputs.to_track)] {
+            if !self.available_tracks(sem).is_empty() && track.is_none() {
+                self.error_message = Some(format!("Please select a track for Semester {}.", sem));
+                return;
+            }
+        }
+        let current_cpi = match inputs.current_cpi_str.parse::<f32>() {
+            Ok(cpi) if (0.0..=10.0).contains(&cpi) => cpi,
+            _ => {
+                self.error_message = Some("Please enter a current CPI between 0 and 10.".to_string());
+                return;
+            }
+        };
+        let target_cpi = match inputs.target_cpi_str.parse::<f32>() {
+            Ok(cpi) if (0.0..=10.0).contains(&cpi) => cpi,
+            _ => {
+                self.error_message = Some("Please enter a target CPI between 0 and 10.".to_string());
+                return;
             }
-        } else {
-            None // SPI not needed for CPI Option 2 directly here
         };
 
-        // --- Set SPI result if calculated ---
-        if let Some(spi) = spi_result {
-            self.calculated_spi = Some(spi);
+        let path = self.target_path().unwrap_or_default();
+        let path_electives = form::path_electives(&path, &self.path_electives);
+        match functions::plan_target_cpi(&programme, batch, &path, &path_electives, from_sem, current_cpi, target_cpi) {
+            Ok(plan) => self.target_plan = Some(plan),
+            Err(err) => self.show_calc_error(err),
         }
+    }
 
+    fn calculate_transcript(&mut self) {
+        let (programme, batch) = match (self.programme.clone(), self.batch) {
+            (Some(p), Some(b)) => (p, b),
+            _ => {
+                self.error_message = Some("Programme or batch not selected.".to_string());
+                return;
+            }
+        };
+        if let Some(i) = self.transcript.iter().position(|row| row.grades.is_empty()) {
+            self.error_message = Some(format!("Please select a track for Semester {}.", i + 1));
+            return;
+        }
+        let row_errors: Vec<(String, String)> = self
+            .transcript
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                let repeat_grades: Vec<String> = row.repeats.iter().map(|r| r.grade.clone()).collect();
+                let mut errors = Self::grade_row_errors(&format!("transcript-grade-{}", i), &row.grades);
+                errors.extend(Self::grade_row_errors(&format!("transcript-repeat-{}", i), &repeat_grades));
+                errors
+            })
+            .collect();
+        if !row_errors.is_empty() {
+            self.mark_grade_rows(row_errors);
+            return;
+        }
 
-        // --- CPI Calculation ---
-        if self.calc_type == Some(1) { // Only calculate CPI if CPI mode is selected
-            if self.sem_no == Some(1) {
-                 if let Some(spi) = spi_result {
-                    self.calculated_cpi = Some(spi); // For Sem 1, CPI = SPI
-                 } // Error handled by SPI calculation part
-            } else if self.calc_cpi_option == Some(1) {
-                 if let Some(spi) = spi_result { // CPI Option 1 depends on successful SPI calculation
-                     match self.cpi_op1_var1_str.parse::<f32>() {
-                        Ok(prev_cpi) => {
-                            let cpi = functions::calculate_cpi_option3(sem_f32, prev_cpi, spi);
-                            self.calculated_cpi = Some(cpi);
-                        }
-                        Err(_) => {
-                            self.error_message = Some("Invalid previous CPI value entered for Option 1.".to_string());
-                            self.calculated_spi = None; // Clear SPI if CPI calc fails due to this input
-                        }
-                    }
-                 } // Error handled by SPI calculation part
-            } else if self.calc_cpi_option == Some(2) {
-                 match (self.cpi_op2_var1_str.parse::<f32>(), self.cpi_op2_var2_str.parse::<f32>()) {
-                    (Ok(prev_cpi), Ok(current_spi)) => {
-                        let cpi = functions::calculate_cpi_option3(sem_f32, prev_cpi, current_spi);
-                        self.calculated_cpi = Some(cpi);
-                        // Display the entered SPI for clarity as well
-                        self.calculated_spi = Some(current_spi);
-                    }
-                    (Err(_), _) => {
-                         self.error_message = Some("Invalid previous CPI value entered for Option 2.".to_string());
-                    }
-                     (_, Err(_)) => {
-                         self.error_message = Some("Invalid current SPI value entered for Option 2.".to_string());
-                    }
-                }
-            } else if self.sem_no.map_or(false, |s| s > 1) {
-                 // If CPI is selected, semester > 1, but no CPI option chosen
-                 self.error_message = Some("Please select a CPI calculation option.".to_string());
-            }
-        } else if self.calc_type.is_none() {
-             self.error_message = Some("Please select calculation type (SPI or CPI).".to_string());
-        }
-
-        // Final check if no results were calculated and no specific error was set yet
-        if self.calculated_spi.is_none() && self.calculated_cpi.is_none() && self.error_message.is_none() {
-             // This case might occur if Calculate is pressed too early, e.g., before 'Done'
-             if !self.done_1 {
-                 self.error_message = Some("Please select semester/options and click 'Done' first.".to_string());
-             } else {
-                 // Default error if something unexpected happened
-                 self.error_message = Some("Calculation could not be performed. Check inputs.".to_string());
-             }
+        let entries = self.transcript_entries(self.transcript.len());
+        match functions::calculate_transcript(&programme, batch, &entries, grades::default_scale(), self.repeat_policy) {
+            Ok(results) => self.transcript_results = Some(results),
+            Err(err) => self.show_calc_error(err),
+        }
+    }
+
+    // Appends the next semester of the curriculum, if it has one, with no grades entered
+    fn add_transcript_semester(&mut self) {
+        let number = self.transcript.len() as u8 + 1;
+        if let Some(curriculum) = self.curriculum() {
+            if curriculum.semester_numbers().contains(&number) {
+                // Semesters with tracks get their courses once a track is picked
+                let course_count = curriculum
+                    .semester(SemesterId::new(number))
+                    .map_or(0, |sem| sem.course_code.len());
+                self.transcript.push(TranscriptRow {
+                    track: None,
+                    grades: vec!["".to_string(); course_count],
+                    electives: vec![None; course_count],
+                    repeats: Vec::new(),
+                });
+            }
+        }
+    }
+
+    // The first `count` transcript rows in the form the calculation takes
+    fn transcript_entries(&self, count: usize) -> Vec<functions::TranscriptEntry> {
+        self.transcript
+            .iter()
+            .take(count)
+            .enumerate()
+            .map(|(i, row)| functions::TranscriptEntry {
+                semester: SemesterId { number: i as u8 + 1, track: row.track },
+                grades: row.grades.clone(),
+                electives: row.electives.clone(),
+                repeats: row.repeats.clone(),
+            })
+            .collect()
+    }
+
+    // Backlogs still open when the semester at `index` starts
+    fn backlogs_before(&self, index: usize) -> Vec<functions::Backlog> {
+        match (self.programme.as_deref(), self.batch) {
+            (Some(programme), Some(batch)) => functions::outstanding_backlogs(programme, batch, &self.transcript_entries(index), grades::default_scale()),
+            _ => Vec::new(),
+        }
+    }
+
+    // A message for every course row whose grade is missing or not on the
+    // scale, keyed by the row id `{prefix}-{index}`.
+    fn grade_row_errors(prefix: &str, grades: &[String]) -> Vec<(String, String)> {
+        form::grade_errors(grades, grades::default_scale())
+            .into_iter()
+            .map(|(i, err)| (format!("{}-{}", prefix, i), form::course_message(&err)))
+            .collect()
+    }
+
+    fn mark_grade_rows(&mut self, row_errors: Vec<(String, String)>) {
+        self.course_errors.extend(row_errors);
+        self.error_message = Some("Please fix the grades marked below.".to_string());
+    }
+
+    // Shows a failed calculation, next to the course row when it names one.
+    fn show_calc_error(&mut self, err: CalcError) {
+        let (prefix, cause) = match &err {
+            CalcError::InSemester(id, inner) => (format!("transcript-grade-{}", id.number - 1), inner.as_ref()),
+            other => ("grade".to_string(), other),
+        };
+        let row_id = match (&err, cause) {
+            (_, CalcError::MissingGrade { index } | CalcError::InvalidGrade { index, .. } | CalcError::InvalidElective { index, .. }) => Some(format!("{}-{}", prefix, index)),
+            (CalcError::InSemester(id, _), CalcError::NotABacklog(course) | CalcError::InvalidRepeatGrade { course, .. }) => {
+                let index = id.number as usize - 1;
+                self.transcript
+                    .get(index)
+                    .and_then(|row| row.repeats.iter().position(|r| r.course == *course))
+                    .map(|repeat| format!("transcript-repeat-{}-{}", index, repeat))
+            }
+            _ => None,
+        };
+        match row_id {
+            Some(id) => self.mark_grade_rows(vec![(id, form::course_message(cause))]),
+            None => self.error_message = Some(form::error_message(&err)),
         }
     }
 }
 
 
+// Saves `contents` as a file by clicking a temporary link to a Blob
+fn download_file(file_name: &str, contents: &str, media_type: &str) -> Result<(), JsValue> {
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(media_type);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&contents.into()), &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let document = web_sys::window().and_then(|w| w.document()).ok_or("no document")?;
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+// Drops the `#...` fragment from the address bar without reloading or adding
+// a history entry
+fn clear_location_hash() {
+    if let Some(window) = web_sys::window() {
+        let location = window.location();
+        if let (Ok(history), Ok(path), Ok(search)) = (window.history(), location.pathname(), location.search()) {
+            let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&format!("{}{}", path, search)));
+        }
+    }
+}
+
 // Entry point for WASM
 #[wasm_bindgen(start)]
 pub fn run_app() {
@@ -0,0 +1,30 @@
+// The saved-state format lives in the core; this adds the browser backend.
+pub use cpi_core::storage::*;
+
+// Browser localStorage via web-sys. Writes are best effort: a full or
+// disabled storage (e.g. private browsing) just means nothing is saved.
+pub struct LocalStorage {
+    inner: web_sys::Storage,
+}
+
+impl LocalStorage {
+    // None if there is no window or the browser denies storage access.
+    pub fn open() -> Option<Self> {
+        let inner = web_sys::window()?.local_storage().ok()??;
+        Some(LocalStorage { inner })
+    }
+}
+
+impl Storage for LocalStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.inner.get_item(key).ok()?
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        let _ = self.inner.set_item(key, value);
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = self.inner.remove_item(key);
+    }
+}